@@ -3,12 +3,20 @@ import type { ActorMethod } from '@dfinity/agent';
 import type { IDL } from '@dfinity/candid';
 
 export interface AddAssetArgs {
+  'fee' : [] | [FeeOptions],
+  'detect_metadata' : [] | [boolean],
   'asset_address' : string,
   'stale_price_threshold' : bigint,
-  'token_decimals' : number,
-  'symbol' : string,
+  'override_feed_validation' : [] | [boolean],
+  'network' : [] | [string],
+  'token_decimals' : [] | [number],
+  'symbol' : [] | [string],
   'price_feed' : string,
 }
+export interface AddAssetResult {
+  'feed_warnings' : Array<string>,
+  'submission' : TransactionSubmission,
+}
 export interface AssetInfo { 'address' : string, 'symbol' : string }
 export interface AssetInfoSymbol {
   'asset_address' : string,
@@ -25,6 +33,61 @@ export interface AssetWithPrice {
   'price' : string,
   'symbol' : string,
 }
+export type BackendError = { 'InconsistentResults' : {
+    'agreeing' : number,
+    'providers' : number,
+    'required' : number,
+  } } |
+  { 'AssetNotFound' : string } |
+  { 'InvalidAddress' : string } |
+  { 'Overflow' : string } |
+  { 'SignerError' : string } |
+  { 'RpcError' : string } |
+  { 'InvalidInput' : string } |
+  { 'InsufficientCycles' : string } |
+  { 'Unauthorized' : string } |
+  { 'ContractRevert' : { 'reason' : string } } |
+  { 'ChecksumMismatch' : { 'provided' : string, 'expected' : string } } |
+  { 'StalePrice' : { 'asset' : string } };
+export interface CachedAssetPrice {
+  'asset' : AssetWithPrice,
+  'fetched_at' : bigint,
+}
+export interface CachedPrices {
+  'last_error' : [] | [string],
+  'assets' : Array<AssetWithPrice>,
+  'network' : string,
+  'owner_address' : string,
+  'fetched_at' : bigint,
+}
+export interface Candle {
+  'low' : string,
+  'decimals' : number,
+  'high' : string,
+  'close' : string,
+  'open' : string,
+  'samples' : number,
+  'start' : bigint,
+}
+export interface CanisterConfig {
+  'networks' : Array<[string, NetworkConfig]>,
+  'default_network' : string,
+}
+export interface ConsensusConfig {
+  'rpc_services' : Array<RpcService>,
+  'strategy' : ConsensusStrategy,
+}
+export type ConsensusStrategy = { 'Equality' : null } |
+  { 'Threshold' : { 'min' : number } };
+export interface ConversionLeg {
+  'asset_address' : string,
+  'price_decimals' : number,
+  'last_updated_time' : bigint,
+  'raw_price' : string,
+  'token_decimals' : number,
+  'price' : string,
+  'symbol' : string,
+}
 export interface ConversionResult {
   'raw' : RawConversionData,
   'output' : string,
@@ -35,6 +98,101 @@ export interface ConversionResult {
   'price' : string,
   'symbol' : string,
 }
+export type EthMainnetService = { 'Alchemy' : null } |
+  { 'Llama' : null } |
+  { 'BlockPi' : null } |
+  { 'Cloudflare' : null } |
+  { 'PublicNode' : null } |
+  { 'Ankr' : null };
+export type EthSepoliaService = { 'Alchemy' : null } |
+  { 'BlockPi' : null } |
+  { 'PublicNode' : null } |
+  { 'Ankr' : null } |
+  { 'Sepolia' : null };
+export interface FeeOptions {
+  'speed' : [] | [FeeSpeed],
+  'max_fee_per_gas_cap' : [] | [string],
+}
+export type FeeSpeed = { 'Fast' : null } |
+  { 'Slow' : null } |
+  { 'Normal' : null };
+export interface FeedInfo {
+  'decimals' : number,
+  'feed_address' : string,
+  'description' : string,
+  'version' : string,
+}
+export interface FeedPrice {
+  'updated_at' : bigint,
+  'decimals' : number,
+  'feed_address' : string,
+  'phase_id' : number,
+  'aggregator_round_id' : string,
+  'raw_price' : string,
+  'price' : string,
+  'round_id' : string,
+  'answered_in_round' : string,
+  'started_at' : bigint,
+}
+export interface FeedValidationReport {
+  'updated_at' : [] | [bigint],
+  'decimals' : [] | [number],
+  'feed_address' : string,
+  'base' : [] | [string],
+  'stale_price_threshold' : bigint,
+  'errors' : Array<string>,
+  'quote' : [] | [string],
+  'description' : [] | [string],
+  'warnings' : Array<string>,
+  'raw_price' : [] | [string],
+  'price' : [] | [string],
+  'passed' : boolean,
+  'symbol' : string,
+  'age_seconds' : [] | [bigint],
+}
+export interface Holding { 'asset_address' : string, 'amount' : string }
+export interface HttpHeader { 'value' : string, 'name' : string }
+export interface InitArgs {
+  'networks' : [] | [Array<[string, NetworkConfig]>],
+  'rpc_service' : [] | [RpcService],
+  'registry_address' : [] | [string],
+  'chain_id' : [] | [bigint],
+  'default_network' : [] | [string],
+}
+export type L2MainnetService = { 'Alchemy' : null } |
+  { 'Llama' : null } |
+  { 'BlockPi' : null } |
+  { 'PublicNode' : null } |
+  { 'Ankr' : null };
+export interface NetworkConfig {
+  'confirmations' : [] | [bigint],
+  'rpc_service' : RpcService,
+  'registry_address' : string,
+  'chain_id' : bigint,
+  'consensus' : [] | [ConsensusConfig],
+}
+export interface PortfolioPosition {
+  'status' : PositionStatus,
+  'asset_address' : string,
+  'weight_percent' : [] | [string],
+  'last_updated_time' : [] | [bigint],
+  'usd_value' : [] | [string],
+  'raw_usd_value' : [] | [string],
+  'price' : [] | [string],
+  'amount' : string,
+  'symbol' : string,
+}
+export interface PortfolioValuation {
+  'unvalued_positions' : number,
+  'total_usd_value' : string,
+  'raw_total_usd_value' : string,
+  'positions' : Array<PortfolioPosition>,
+  'owner_address' : string,
+}
+export type PositionStatus = { 'NotRegistered' : null } |
+  { 'BalanceUnavailable' : { 'reason' : string } } |
+  { 'Valued' : null } |
+  { 'StalePrice' : null };
 export interface PriceFeedDetails {
   'updated_at' : bigint,
   'answer' : bigint,
@@ -45,58 +203,326 @@ export interface PriceFeedDetails {
 export interface PriceInfo {
   'decimals' : number,
   'raw_price' : string,
+  'fetched_at' : bigint,
   'formatted_price' : string,
 }
+export interface PricePoint {
+  'decimals' : number,
+  'timestamp' : bigint,
+  'raw_price' : string,
+  'price' : string,
+  'observed_at' : bigint,
+}
+export interface PriceRefreshConfig {
+  'owners' : Array<string>,
+  'network' : [] | [string],
+  'interval_seconds' : bigint,
+}
 export interface RawConversionData { 'price' : string, 'amount' : string }
-export interface RemoveAssetArgs { 'asset_address' : string }
+export interface RemoveAssetArgs {
+  'fee' : [] | [FeeOptions],
+  'asset_address' : string,
+  'network' : [] | [string],
+}
 export type Result = { 'Ok' : string } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_1 = { 'Ok' : Array<ConversionResult> } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
+export type Result_10 = { 'Ok' : bigint } |
+  { 'Err' : BackendError };
+export type Result_11 = { 'Ok' : null } |
+  { 'Err' : BackendError };
+export type Result_12 = { 'Ok' : CachedAssetPrice } |
+  { 'Err' : BackendError };
+export type Result_13 = { 'Ok' : CachedPrices } |
+  { 'Err' : BackendError };
+export type Result_14 = { 'Ok' : TransactionSubmission } |
+  { 'Err' : BackendError };
+export type Result_15 = { 'Ok' : TrackedTransaction } |
+  { 'Err' : BackendError };
+export type Result_16 = { 'Ok' : TransactionStatus } |
+  { 'Err' : BackendError };
+export type Result_17 = { 'Ok' : SimulationResult } |
+  { 'Err' : BackendError };
+export type Result_18 = { 'Ok' : FeedInfo } |
+  { 'Err' : BackendError };
+export type Result_19 = { 'Ok' : FeedPrice } |
+  { 'Err' : BackendError };
 export type Result_2 = { 'Ok' : Array<AssetInfo> } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
+export type Result_20 = { 'Ok' : Array<FeedPrice> } |
+  { 'Err' : BackendError };
+export type Result_21 = { 'Ok' : Array<Candle> } |
+  { 'Err' : BackendError };
+export type Result_22 = { 'Ok' : Array<PricePoint> } |
+  { 'Err' : BackendError };
+export type Result_23 = { 'Ok' : TwapResult } |
+  { 'Err' : BackendError };
+export type Result_24 = { 'Ok' : TokenConversionResult } |
+  { 'Err' : BackendError };
+export type Result_25 = { 'Ok' : PortfolioValuation } |
+  { 'Err' : BackendError };
+export type Result_26 = { 'Ok' : TokenMetadataResult } |
+  { 'Err' : BackendError };
+export type Result_27 = { 'Ok' : WalletValuation } |
+  { 'Err' : BackendError };
+export type Result_28 = { 'Ok' : FeedValidationReport } |
+  { 'Err' : BackendError };
+export type Result_29 = { 'Ok' : Array<Result_14> } |
+  { 'Err' : BackendError };
 export type Result_3 = { 'Ok' : Array<AssetWithPrice> } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
+export type Result_30 = { 'Ok' : AddAssetResult } |
+  { 'Err' : BackendError };
+export type Result_31 = { 'Ok' : Array<Result_30> } |
+  { 'Err' : BackendError };
 export type Result_4 = { 'Ok' : AssetInfoSymbol } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_5 = { 'Ok' : PriceFeedDetails } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_6 = { 'Ok' : TokenAmountResult } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_7 = { 'Ok' : TokenPriceResult } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_8 = { 'Ok' : UsdValueResult } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
 export type Result_9 = { 'Ok' : PriceInfo } |
-  { 'Err' : string };
+  { 'Err' : BackendError };
+export type RoundingMode = { 'Up' : null } |
+  { 'Down' : null } |
+  { 'HalfUp' : null } |
+  { 'HalfEven' : null };
+export interface RpcApi { 'url' : string, 'headers' : [] | [Array<HttpHeader>] }
+export type RpcService = { 'EthSepolia' : EthSepoliaService } |
+  { 'BaseMainnet' : L2MainnetService } |
+  { 'Custom' : RpcApi } |
+  { 'OptimismMainnet' : L2MainnetService } |
+  { 'ArbitrumOne' : L2MainnetService } |
+  { 'EthMainnet' : EthMainnetService } |
+  { 'Provider' : bigint };
+export interface SimulationResult {
+  'to' : string,
+  'max_priority_fee_per_gas' : [] | [string],
+  'from' : string,
+  'network' : string,
+  'max_fee_per_gas' : [] | [string],
+  'revert_reason' : [] | [string],
+  'gas_limit' : [] | [string],
+  'success' : boolean,
+  'max_fee' : [] | [string],
+  'estimated_gas' : [] | [string],
+  'expected_fee' : [] | [string],
+}
 export interface TokenAmountResult { 'raw_amount' : string, 'amount' : string }
+export interface TokenConversionResult {
+  'to' : ConversionLeg,
+  'output' : string,
+  'from' : ConversionLeg,
+  'raw_output' : string,
+  'raw_input' : string,
+  'input' : string,
+  'rounding' : RoundingMode,
+}
+export interface TokenMetadataResult {
+  'decimals' : number,
+  'token_address' : string,
+  'symbol' : string,
+}
 export interface TokenPriceResult {
   'decimals' : number,
   'raw_price' : bigint,
   'price' : string,
 }
+export interface TrackedTransaction {
+  'status' : TransactionStatus,
+  'method' : string,
+  'caller' : Principal,
+  'input' : Uint8Array | number[],
+  'last_checked' : bigint,
+  'submission' : TransactionSubmission,
+  'arguments' : string,
+}
+export type TransactionStatus = { 'Reverted' : {
+    'block_number' : bigint,
+    'gas_used' : string,
+    'reason' : string,
+  } } |
+  { 'Confirmed' : { 'block_number' : bigint, 'gas_used' : string } } |
+  { 'TimedOut' : null } |
+  { 'Dropped' : { 'since' : bigint } } |
+  { 'Replaced' : { 'transaction_hash' : string } } |
+  { 'Pending' : null };
+export interface TransactionSubmission {
+  'to' : string,
+  'transaction_hash' : string,
+  'max_priority_fee_per_gas' : string,
+  'from' : string,
+  'network' : string,
+  'max_fee_per_gas' : string,
+  'chain_id' : bigint,
+  'nonce' : bigint,
+  'gas_limit' : string,
+  'submitted_at' : bigint,
+}
+export interface TwapResult {
+  'end' : bigint,
+  'decimals' : number,
+  'asset_address' : string,
+  'covered_seconds' : bigint,
+  'samples' : number,
+  'start' : bigint,
+  'raw_price' : string,
+  'price' : string,
+  'coverage_gap_seconds' : bigint,
+}
+export interface UpdateSymbolArgs {
+  'fee' : [] | [FeeOptions],
+  'asset_address' : string,
+  'network' : [] | [string],
+  'symbol' : string,
+}
 export interface UsdValueResult {
   'token_amount' : string,
   'asset_address' : string,
   'usd_value' : string,
   'raw_result' : string,
 }
+export interface WalletValuation {
+  'principal' : Principal,
+  'wallet_address' : string,
+  'valuation' : PortfolioValuation,
+}
 export interface _SERVICE {
-  'add_asset' : ActorMethod<[AddAssetArgs], Result>,
-  'convert_tokens_to_usd' : ActorMethod<[string, Array<string>], Result_1>,
-  'convert_usd_to_tokens' : ActorMethod<[string, Array<string>], Result_1>,
+  'add_asset' : ActorMethod<[AddAssetArgs], Result_30>,
+  'add_assets' : ActorMethod<[Array<AddAssetArgs>], Result_31>,
+  'add_network' : ActorMethod<[string, NetworkConfig], Result_11>,
+  'cancel_transaction' : ActorMethod<[string], Result_14>,
+  'convert_token_to_token_live' : ActorMethod<
+    [string, string, string, string, [] | [RoundingMode], [] | [string]],
+    Result_24
+  >,
+  'convert_tokens_to_usd_live' : ActorMethod<
+    [string, Array<string>, [] | [string]],
+    Result_1
+  >,
+  'convert_usd_to_tokens_live' : ActorMethod<
+    [string, Array<string>, [] | [string]],
+    Result_1
+  >,
   'get_address' : ActorMethod<[[] | [Principal]], Result>,
-  'get_all_assets' : ActorMethod<[string], Result_2>,
-  'get_all_assets_with_prices' : ActorMethod<[string], Result_3>,
-  'get_asset_by_symbol' : ActorMethod<[string, string], Result_4>,
-  'get_balance' : ActorMethod<[[] | [Principal]], Result>,
-  'get_price_feed_details' : ActorMethod<[string, string], Result_5>,
-  'get_token_amount' : ActorMethod<[string, string, string, number], Result_6>,
-  'get_token_price' : ActorMethod<[string, string], Result_7>,
-  'get_token_price_by_symbol' : ActorMethod<[string, string], Result_7>,
-  'get_usd_value' : ActorMethod<[string, string, string, number], Result_8>,
-  'remove_asset' : ActorMethod<[RemoveAssetArgs], Result>,
-  'safe_get_price' : ActorMethod<[string, string], Result_9>,
+  'get_all_assets' : ActorMethod<[string, [] | [string]], Result_2>,
+  'get_all_assets_live' : ActorMethod<[string, [] | [string]], Result_2>,
+  'get_all_assets_with_prices' : ActorMethod<[string, [] | [string]], Result_3>,
+  'get_all_assets_with_prices_live' : ActorMethod<
+    [string, [] | [string]],
+    Result_3
+  >,
+  'get_asset_by_symbol_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_4
+  >,
+  'get_balance_live' : ActorMethod<[[] | [Principal], [] | [string]], Result>,
+  'get_cached_price' : ActorMethod<[string, string, [] | [string]], Result_12>,
+  'get_cached_prices' : ActorMethod<[string, [] | [string]], Result_13>,
+  'get_candles' : ActorMethod<
+    [string, string, bigint, [] | [string]],
+    Result_21
+  >,
+  'get_config' : ActorMethod<[], CanisterConfig>,
+  'get_feed_info_live' : ActorMethod<[string, [] | [string]], Result_18>,
+  'get_feed_price_at_live' : ActorMethod<
+    [string, bigint, [] | [string]],
+    Result_19
+  >,
+  'get_feed_price_live' : ActorMethod<
+    [string, [] | [bigint], [] | [string]],
+    Result_19
+  >,
+  'get_feed_round_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_19
+  >,
+  'get_feed_rounds_live' : ActorMethod<
+    [string, [] | [string], number, [] | [string]],
+    Result_20
+  >,
+  'get_price_feed_details_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_5
+  >,
+  'get_price_history' : ActorMethod<
+    [string, string, bigint, bigint, [] | [string]],
+    Result_22
+  >,
+  'get_price_refresh_config' : ActorMethod<[], PriceRefreshConfig>,
+  'get_token_amount_live' : ActorMethod<
+    [string, string, string, number, [] | [string]],
+    Result_6
+  >,
+  'get_token_metadata_live' : ActorMethod<[string, [] | [string]], Result_26>,
+  'get_token_price' : ActorMethod<[string, string, [] | [string]], Result_7>,
+  'get_token_price_by_symbol' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_7
+  >,
+  'get_token_price_by_symbol_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_7
+  >,
+  'get_token_price_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_7
+  >,
+  'get_transaction' : ActorMethod<[string], Result_15>,
+  'get_transaction_status' : ActorMethod<[string], Result_16>,
+  'get_twap' : ActorMethod<
+    [string, string, bigint, [] | [number], [] | [string]],
+    Result_23
+  >,
+  'get_twap_live' : ActorMethod<
+    [string, string, bigint, [] | [number], [] | [string]],
+    Result_23
+  >,
+  'get_usd_value_live' : ActorMethod<
+    [string, string, string, number, [] | [string]],
+    Result_8
+  >,
+  'get_wallet_value_live' : ActorMethod<
+    [string, [] | [Principal], [] | [string]],
+    Result_27
+  >,
+  'refresh_cached_prices' : ActorMethod<[], Result_11>,
+  'remove_asset' : ActorMethod<[RemoveAssetArgs], Result_14>,
+  'remove_assets' : ActorMethod<[Array<RemoveAssetArgs>], Result_29>,
+  'remove_network' : ActorMethod<[string], Result_11>,
+  'resync_nonce' : ActorMethod<[[] | [string]], Result_10>,
+  'safe_get_price' : ActorMethod<[string, string, [] | [string]], Result_9>,
+  'safe_get_price_live' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_9
+  >,
+  'set_chain_id' : ActorMethod<[bigint, [] | [string]], Result_11>,
+  'set_confirmations' : ActorMethod<[bigint, [] | [string]], Result_11>,
+  'set_consensus' : ActorMethod<
+    [[] | [ConsensusConfig], [] | [string]],
+    Result_11
+  >,
+  'set_default_network' : ActorMethod<[string], Result_11>,
+  'set_price_refresh_config' : ActorMethod<[PriceRefreshConfig], Result_11>,
+  'set_registry_address' : ActorMethod<[string, [] | [string]], Result>,
+  'set_rpc_service' : ActorMethod<[RpcService, [] | [string]], Result_11>,
+  'simulate_add_asset' : ActorMethod<[AddAssetArgs], Result_17>,
+  'simulate_remove_asset' : ActorMethod<[RemoveAssetArgs], Result_17>,
+  'speed_up_transaction' : ActorMethod<[string, number], Result_14>,
+  'update_symbol' : ActorMethod<[UpdateSymbolArgs], Result_14>,
+  'validate_price_feed_live' : ActorMethod<
+    [string, string, [] | [bigint], [] | [string]],
+    Result_28
+  >,
+  'value_portfolio_live' : ActorMethod<
+    [string, Array<Holding>, [] | [string]],
+    Result_25
+  >,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
 export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
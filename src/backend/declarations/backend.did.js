@@ -1,12 +1,153 @@
 export const idlFactory = ({ IDL }) => {
+  const FeeSpeed = IDL.Variant({
+    'Fast' : IDL.Null,
+    'Slow' : IDL.Null,
+    'Normal' : IDL.Null,
+  });
+  const FeeOptions = IDL.Record({
+    'speed' : IDL.Opt(FeeSpeed),
+    'max_fee_per_gas_cap' : IDL.Opt(IDL.Text),
+  });
   const AddAssetArgs = IDL.Record({
+    'fee' : IDL.Opt(FeeOptions),
+    'detect_metadata' : IDL.Opt(IDL.Bool),
     'asset_address' : IDL.Text,
     'stale_price_threshold' : IDL.Nat64,
+    'override_feed_validation' : IDL.Opt(IDL.Bool),
+    'network' : IDL.Opt(IDL.Text),
+    'token_decimals' : IDL.Opt(IDL.Nat8),
+    'symbol' : IDL.Opt(IDL.Text),
+    'price_feed' : IDL.Text,
+  });
+  const TransactionSubmission = IDL.Record({
+    'to' : IDL.Text,
+    'transaction_hash' : IDL.Text,
+    'max_priority_fee_per_gas' : IDL.Text,
+    'from' : IDL.Text,
+    'network' : IDL.Text,
+    'max_fee_per_gas' : IDL.Text,
+    'chain_id' : IDL.Nat64,
+    'nonce' : IDL.Nat64,
+    'gas_limit' : IDL.Text,
+    'submitted_at' : IDL.Nat64,
+  });
+  const AddAssetResult = IDL.Record({
+    'feed_warnings' : IDL.Vec(IDL.Text),
+    'submission' : TransactionSubmission,
+  });
+  const BackendError = IDL.Variant({
+    'InconsistentResults' : IDL.Record({
+      'agreeing' : IDL.Nat8,
+      'providers' : IDL.Nat8,
+      'required' : IDL.Nat8,
+    }),
+    'AssetNotFound' : IDL.Text,
+    'InvalidAddress' : IDL.Text,
+    'Overflow' : IDL.Text,
+    'SignerError' : IDL.Text,
+    'RpcError' : IDL.Text,
+    'InvalidInput' : IDL.Text,
+    'InsufficientCycles' : IDL.Text,
+    'Unauthorized' : IDL.Text,
+    'ContractRevert' : IDL.Record({ 'reason' : IDL.Text }),
+    'ChecksumMismatch' : IDL.Record({
+      'provided' : IDL.Text,
+      'expected' : IDL.Text,
+    }),
+    'StalePrice' : IDL.Record({ 'asset' : IDL.Text }),
+  });
+  const Result_30 = IDL.Variant({
+    'Ok' : AddAssetResult,
+    'Err' : BackendError,
+  });
+  const Result_31 = IDL.Variant({
+    'Ok' : IDL.Vec(Result_30),
+    'Err' : BackendError,
+  });
+  const EthSepoliaService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+    'Sepolia' : IDL.Null,
+  });
+  const L2MainnetService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'Llama' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+  });
+  const HttpHeader = IDL.Record({ 'value' : IDL.Text, 'name' : IDL.Text });
+  const RpcApi = IDL.Record({
+    'url' : IDL.Text,
+    'headers' : IDL.Opt(IDL.Vec(HttpHeader)),
+  });
+  const EthMainnetService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'Llama' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'Cloudflare' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+  });
+  const RpcService = IDL.Variant({
+    'EthSepolia' : EthSepoliaService,
+    'BaseMainnet' : L2MainnetService,
+    'Custom' : RpcApi,
+    'OptimismMainnet' : L2MainnetService,
+    'ArbitrumOne' : L2MainnetService,
+    'EthMainnet' : EthMainnetService,
+    'Provider' : IDL.Nat64,
+  });
+  const ConsensusStrategy = IDL.Variant({
+    'Equality' : IDL.Null,
+    'Threshold' : IDL.Record({ 'min' : IDL.Nat8 }),
+  });
+  const ConsensusConfig = IDL.Record({
+    'rpc_services' : IDL.Vec(RpcService),
+    'strategy' : ConsensusStrategy,
+  });
+  const NetworkConfig = IDL.Record({
+    'confirmations' : IDL.Opt(IDL.Nat64),
+    'rpc_service' : RpcService,
+    'registry_address' : IDL.Text,
+    'chain_id' : IDL.Nat64,
+    'consensus' : IDL.Opt(ConsensusConfig),
+  });
+  const Result_11 = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : BackendError });
+  const Result_14 = IDL.Variant({
+    'Ok' : TransactionSubmission,
+    'Err' : BackendError,
+  });
+  const RoundingMode = IDL.Variant({
+    'Up' : IDL.Null,
+    'Down' : IDL.Null,
+    'HalfUp' : IDL.Null,
+    'HalfEven' : IDL.Null,
+  });
+  const ConversionLeg = IDL.Record({
+    'asset_address' : IDL.Text,
+    'price_decimals' : IDL.Nat8,
+    'last_updated_time' : IDL.Nat64,
+    'raw_price' : IDL.Text,
     'token_decimals' : IDL.Nat8,
+    'price' : IDL.Text,
     'symbol' : IDL.Text,
-    'price_feed' : IDL.Text,
   });
-  const Result = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : IDL.Text });
+  const TokenConversionResult = IDL.Record({
+    'to' : ConversionLeg,
+    'output' : IDL.Text,
+    'from' : ConversionLeg,
+    'raw_output' : IDL.Text,
+    'raw_input' : IDL.Text,
+    'input' : IDL.Text,
+    'rounding' : RoundingMode,
+  });
+  const Result_24 = IDL.Variant({
+    'Ok' : TokenConversionResult,
+    'Err' : BackendError,
+  });
   const RawConversionData = IDL.Record({
     'price' : IDL.Text,
     'amount' : IDL.Text,
@@ -23,10 +164,14 @@ export const idlFactory = ({ IDL }) => {
   });
   const Result_1 = IDL.Variant({
     'Ok' : IDL.Vec(ConversionResult),
-    'Err' : IDL.Text,
+    'Err' : BackendError,
   });
+  const Result = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : BackendError });
   const AssetInfo = IDL.Record({ 'address' : IDL.Text, 'symbol' : IDL.Text });
-  const Result_2 = IDL.Variant({ 'Ok' : IDL.Vec(AssetInfo), 'Err' : IDL.Text });
+  const Result_2 = IDL.Variant({
+    'Ok' : IDL.Vec(AssetInfo),
+    'Err' : BackendError,
+  });
   const AssetWithPrice = IDL.Record({
     'decimals' : IDL.Nat8,
     'last_updated' : IDL.Text,
@@ -37,7 +182,7 @@ export const idlFactory = ({ IDL }) => {
   });
   const Result_3 = IDL.Variant({
     'Ok' : IDL.Vec(AssetWithPrice),
-    'Err' : IDL.Text,
+    'Err' : BackendError,
   });
   const AssetInfoSymbol = IDL.Record({
     'asset_address' : IDL.Text,
@@ -46,7 +191,67 @@ export const idlFactory = ({ IDL }) => {
     'token_decimals' : IDL.Nat8,
     'price_feed' : IDL.Text,
   });
-  const Result_4 = IDL.Variant({ 'Ok' : AssetInfoSymbol, 'Err' : IDL.Text });
+  const Result_4 = IDL.Variant({
+    'Ok' : AssetInfoSymbol,
+    'Err' : BackendError,
+  });
+  const CachedAssetPrice = IDL.Record({
+    'asset' : AssetWithPrice,
+    'fetched_at' : IDL.Nat64,
+  });
+  const Result_12 = IDL.Variant({
+    'Ok' : CachedAssetPrice,
+    'Err' : BackendError,
+  });
+  const CachedPrices = IDL.Record({
+    'last_error' : IDL.Opt(IDL.Text),
+    'assets' : IDL.Vec(AssetWithPrice),
+    'network' : IDL.Text,
+    'owner_address' : IDL.Text,
+    'fetched_at' : IDL.Nat64,
+  });
+  const Result_13 = IDL.Variant({ 'Ok' : CachedPrices, 'Err' : BackendError });
+  const Candle = IDL.Record({
+    'low' : IDL.Text,
+    'decimals' : IDL.Nat8,
+    'high' : IDL.Text,
+    'close' : IDL.Text,
+    'open' : IDL.Text,
+    'samples' : IDL.Nat32,
+    'start' : IDL.Nat64,
+  });
+  const Result_21 = IDL.Variant({
+    'Ok' : IDL.Vec(Candle),
+    'Err' : BackendError,
+  });
+  const CanisterConfig = IDL.Record({
+    'networks' : IDL.Vec(IDL.Tuple(IDL.Text, NetworkConfig)),
+    'default_network' : IDL.Text,
+  });
+  const FeedInfo = IDL.Record({
+    'decimals' : IDL.Nat8,
+    'feed_address' : IDL.Text,
+    'description' : IDL.Text,
+    'version' : IDL.Text,
+  });
+  const Result_18 = IDL.Variant({ 'Ok' : FeedInfo, 'Err' : BackendError });
+  const FeedPrice = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'decimals' : IDL.Nat8,
+    'feed_address' : IDL.Text,
+    'phase_id' : IDL.Nat16,
+    'aggregator_round_id' : IDL.Text,
+    'raw_price' : IDL.Text,
+    'price' : IDL.Text,
+    'round_id' : IDL.Text,
+    'answered_in_round' : IDL.Text,
+    'started_at' : IDL.Nat64,
+  });
+  const Result_19 = IDL.Variant({ 'Ok' : FeedPrice, 'Err' : BackendError });
+  const Result_20 = IDL.Variant({
+    'Ok' : IDL.Vec(FeedPrice),
+    'Err' : BackendError,
+  });
   const PriceFeedDetails = IDL.Record({
     'updated_at' : IDL.Nat64,
     'answer' : IDL.Int,
@@ -54,68 +259,482 @@ export const idlFactory = ({ IDL }) => {
     'answered_in_round' : IDL.Text,
     'started_at' : IDL.Nat64,
   });
-  const Result_5 = IDL.Variant({ 'Ok' : PriceFeedDetails, 'Err' : IDL.Text });
+  const Result_5 = IDL.Variant({
+    'Ok' : PriceFeedDetails,
+    'Err' : BackendError,
+  });
+  const PricePoint = IDL.Record({
+    'decimals' : IDL.Nat8,
+    'timestamp' : IDL.Nat64,
+    'raw_price' : IDL.Text,
+    'price' : IDL.Text,
+    'observed_at' : IDL.Nat64,
+  });
+  const Result_22 = IDL.Variant({
+    'Ok' : IDL.Vec(PricePoint),
+    'Err' : BackendError,
+  });
+  const PriceRefreshConfig = IDL.Record({
+    'owners' : IDL.Vec(IDL.Text),
+    'network' : IDL.Opt(IDL.Text),
+    'interval_seconds' : IDL.Nat64,
+  });
   const TokenAmountResult = IDL.Record({
     'raw_amount' : IDL.Text,
     'amount' : IDL.Text,
   });
-  const Result_6 = IDL.Variant({ 'Ok' : TokenAmountResult, 'Err' : IDL.Text });
+  const Result_6 = IDL.Variant({
+    'Ok' : TokenAmountResult,
+    'Err' : BackendError,
+  });
+  const TokenMetadataResult = IDL.Record({
+    'decimals' : IDL.Nat8,
+    'token_address' : IDL.Text,
+    'symbol' : IDL.Text,
+  });
+  const Result_26 = IDL.Variant({
+    'Ok' : TokenMetadataResult,
+    'Err' : BackendError,
+  });
   const TokenPriceResult = IDL.Record({
     'decimals' : IDL.Nat8,
     'raw_price' : IDL.Int,
     'price' : IDL.Text,
   });
-  const Result_7 = IDL.Variant({ 'Ok' : TokenPriceResult, 'Err' : IDL.Text });
+  const Result_7 = IDL.Variant({
+    'Ok' : TokenPriceResult,
+    'Err' : BackendError,
+  });
+  const TransactionStatus = IDL.Variant({
+    'Reverted' : IDL.Record({
+      'block_number' : IDL.Nat64,
+      'gas_used' : IDL.Text,
+      'reason' : IDL.Text,
+    }),
+    'Confirmed' : IDL.Record({
+      'block_number' : IDL.Nat64,
+      'gas_used' : IDL.Text,
+    }),
+    'TimedOut' : IDL.Null,
+    'Dropped' : IDL.Record({ 'since' : IDL.Nat64 }),
+    'Replaced' : IDL.Record({ 'transaction_hash' : IDL.Text }),
+    'Pending' : IDL.Null,
+  });
+  const TrackedTransaction = IDL.Record({
+    'status' : TransactionStatus,
+    'method' : IDL.Text,
+    'caller' : IDL.Principal,
+    'input' : IDL.Vec(IDL.Nat8),
+    'last_checked' : IDL.Nat64,
+    'submission' : TransactionSubmission,
+    'arguments' : IDL.Text,
+  });
+  const Result_15 = IDL.Variant({
+    'Ok' : TrackedTransaction,
+    'Err' : BackendError,
+  });
+  const Result_16 = IDL.Variant({
+    'Ok' : TransactionStatus,
+    'Err' : BackendError,
+  });
+  const TwapResult = IDL.Record({
+    'end' : IDL.Nat64,
+    'decimals' : IDL.Nat8,
+    'asset_address' : IDL.Text,
+    'covered_seconds' : IDL.Nat64,
+    'samples' : IDL.Nat32,
+    'start' : IDL.Nat64,
+    'raw_price' : IDL.Text,
+    'price' : IDL.Text,
+    'coverage_gap_seconds' : IDL.Nat64,
+  });
+  const Result_23 = IDL.Variant({ 'Ok' : TwapResult, 'Err' : BackendError });
   const UsdValueResult = IDL.Record({
     'token_amount' : IDL.Text,
     'asset_address' : IDL.Text,
     'usd_value' : IDL.Text,
     'raw_result' : IDL.Text,
   });
-  const Result_8 = IDL.Variant({ 'Ok' : UsdValueResult, 'Err' : IDL.Text });
-  const RemoveAssetArgs = IDL.Record({ 'asset_address' : IDL.Text });
+  const Result_8 = IDL.Variant({ 'Ok' : UsdValueResult, 'Err' : BackendError });
+  const PositionStatus = IDL.Variant({
+    'NotRegistered' : IDL.Null,
+    'BalanceUnavailable' : IDL.Record({ 'reason' : IDL.Text }),
+    'Valued' : IDL.Null,
+    'StalePrice' : IDL.Null,
+  });
+  const PortfolioPosition = IDL.Record({
+    'status' : PositionStatus,
+    'asset_address' : IDL.Text,
+    'weight_percent' : IDL.Opt(IDL.Text),
+    'last_updated_time' : IDL.Opt(IDL.Nat64),
+    'usd_value' : IDL.Opt(IDL.Text),
+    'raw_usd_value' : IDL.Opt(IDL.Text),
+    'price' : IDL.Opt(IDL.Text),
+    'amount' : IDL.Text,
+    'symbol' : IDL.Text,
+  });
+  const PortfolioValuation = IDL.Record({
+    'unvalued_positions' : IDL.Nat32,
+    'total_usd_value' : IDL.Text,
+    'raw_total_usd_value' : IDL.Text,
+    'positions' : IDL.Vec(PortfolioPosition),
+    'owner_address' : IDL.Text,
+  });
+  const WalletValuation = IDL.Record({
+    'principal' : IDL.Principal,
+    'wallet_address' : IDL.Text,
+    'valuation' : PortfolioValuation,
+  });
+  const Result_27 = IDL.Variant({
+    'Ok' : WalletValuation,
+    'Err' : BackendError,
+  });
+  const RemoveAssetArgs = IDL.Record({
+    'fee' : IDL.Opt(FeeOptions),
+    'asset_address' : IDL.Text,
+    'network' : IDL.Opt(IDL.Text),
+  });
+  const Result_29 = IDL.Variant({
+    'Ok' : IDL.Vec(Result_14),
+    'Err' : BackendError,
+  });
+  const Result_10 = IDL.Variant({ 'Ok' : IDL.Nat64, 'Err' : BackendError });
   const PriceInfo = IDL.Record({
     'decimals' : IDL.Nat8,
     'raw_price' : IDL.Text,
+    'fetched_at' : IDL.Nat64,
     'formatted_price' : IDL.Text,
   });
-  const Result_9 = IDL.Variant({ 'Ok' : PriceInfo, 'Err' : IDL.Text });
+  const Result_9 = IDL.Variant({ 'Ok' : PriceInfo, 'Err' : BackendError });
+  const SimulationResult = IDL.Record({
+    'to' : IDL.Text,
+    'max_priority_fee_per_gas' : IDL.Opt(IDL.Text),
+    'from' : IDL.Text,
+    'network' : IDL.Text,
+    'max_fee_per_gas' : IDL.Opt(IDL.Text),
+    'revert_reason' : IDL.Opt(IDL.Text),
+    'gas_limit' : IDL.Opt(IDL.Text),
+    'success' : IDL.Bool,
+    'max_fee' : IDL.Opt(IDL.Text),
+    'estimated_gas' : IDL.Opt(IDL.Text),
+    'expected_fee' : IDL.Opt(IDL.Text),
+  });
+  const Result_17 = IDL.Variant({
+    'Ok' : SimulationResult,
+    'Err' : BackendError,
+  });
+  const UpdateSymbolArgs = IDL.Record({
+    'fee' : IDL.Opt(FeeOptions),
+    'asset_address' : IDL.Text,
+    'network' : IDL.Opt(IDL.Text),
+    'symbol' : IDL.Text,
+  });
+  const FeedValidationReport = IDL.Record({
+    'updated_at' : IDL.Opt(IDL.Nat64),
+    'decimals' : IDL.Opt(IDL.Nat8),
+    'feed_address' : IDL.Text,
+    'base' : IDL.Opt(IDL.Text),
+    'stale_price_threshold' : IDL.Nat64,
+    'errors' : IDL.Vec(IDL.Text),
+    'quote' : IDL.Opt(IDL.Text),
+    'description' : IDL.Opt(IDL.Text),
+    'warnings' : IDL.Vec(IDL.Text),
+    'raw_price' : IDL.Opt(IDL.Text),
+    'price' : IDL.Opt(IDL.Text),
+    'passed' : IDL.Bool,
+    'symbol' : IDL.Text,
+    'age_seconds' : IDL.Opt(IDL.Nat64),
+  });
+  const Result_28 = IDL.Variant({
+    'Ok' : FeedValidationReport,
+    'Err' : BackendError,
+  });
+  const Holding = IDL.Record({
+    'asset_address' : IDL.Text,
+    'amount' : IDL.Text,
+  });
+  const Result_25 = IDL.Variant({
+    'Ok' : PortfolioValuation,
+    'Err' : BackendError,
+  });
   return IDL.Service({
-    'add_asset' : IDL.Func([AddAssetArgs], [Result], []),
-    'convert_tokens_to_usd' : IDL.Func(
-        [IDL.Text, IDL.Vec(IDL.Text)],
+    'add_asset' : IDL.Func([AddAssetArgs], [Result_30], []),
+    'add_assets' : IDL.Func([IDL.Vec(AddAssetArgs)], [Result_31], []),
+    'add_network' : IDL.Func([IDL.Text, NetworkConfig], [Result_11], []),
+    'cancel_transaction' : IDL.Func([IDL.Text], [Result_14], []),
+    'convert_token_to_token_live' : IDL.Func(
+        [
+          IDL.Text,
+          IDL.Text,
+          IDL.Text,
+          IDL.Text,
+          IDL.Opt(RoundingMode),
+          IDL.Opt(IDL.Text),
+        ],
+        [Result_24],
+        [],
+      ),
+    'convert_tokens_to_usd_live' : IDL.Func(
+        [IDL.Text, IDL.Vec(IDL.Text), IDL.Opt(IDL.Text)],
         [Result_1],
         [],
       ),
-    'convert_usd_to_tokens' : IDL.Func(
-        [IDL.Text, IDL.Vec(IDL.Text)],
+    'convert_usd_to_tokens_live' : IDL.Func(
+        [IDL.Text, IDL.Vec(IDL.Text), IDL.Opt(IDL.Text)],
         [Result_1],
         [],
       ),
     'get_address' : IDL.Func([IDL.Opt(IDL.Principal)], [Result], []),
-    'get_all_assets' : IDL.Func([IDL.Text], [Result_2], []),
-    'get_all_assets_with_prices' : IDL.Func([IDL.Text], [Result_3], []),
-    'get_asset_by_symbol' : IDL.Func([IDL.Text, IDL.Text], [Result_4], []),
-    'get_balance' : IDL.Func([IDL.Opt(IDL.Principal)], [Result], []),
-    'get_price_feed_details' : IDL.Func([IDL.Text, IDL.Text], [Result_5], []),
-    'get_token_amount' : IDL.Func(
-        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat8],
+    'get_all_assets' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_2],
+        ['query'],
+      ),
+    'get_all_assets_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_2],
+        [],
+      ),
+    'get_all_assets_with_prices' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_3],
+        ['query'],
+      ),
+    'get_all_assets_with_prices_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_3],
+        [],
+      ),
+    'get_asset_by_symbol_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_4],
+        [],
+      ),
+    'get_balance_live' : IDL.Func(
+        [IDL.Opt(IDL.Principal), IDL.Opt(IDL.Text)],
+        [Result],
+        [],
+      ),
+    'get_cached_price' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_12],
+        ['query'],
+      ),
+    'get_cached_prices' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_13],
+        ['query'],
+      ),
+    'get_candles' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Opt(IDL.Text)],
+        [Result_21],
+        ['query'],
+      ),
+    'get_config' : IDL.Func([], [CanisterConfig], ['query']),
+    'get_feed_info_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_18],
+        [],
+      ),
+    'get_feed_price_at_live' : IDL.Func(
+        [IDL.Text, IDL.Nat64, IDL.Opt(IDL.Text)],
+        [Result_19],
+        [],
+      ),
+    'get_feed_price_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Nat64), IDL.Opt(IDL.Text)],
+        [Result_19],
+        [],
+      ),
+    'get_feed_round_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_19],
+        [],
+      ),
+    'get_feed_rounds_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text), IDL.Nat32, IDL.Opt(IDL.Text)],
+        [Result_20],
+        [],
+      ),
+    'get_price_feed_details_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_5],
+        [],
+      ),
+    'get_price_history' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Nat64, IDL.Opt(IDL.Text)],
+        [Result_22],
+        ['query'],
+      ),
+    'get_price_refresh_config' : IDL.Func([], [PriceRefreshConfig], ['query']),
+    'get_token_amount_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat8, IDL.Opt(IDL.Text)],
         [Result_6],
         [],
       ),
-    'get_token_price' : IDL.Func([IDL.Text, IDL.Text], [Result_7], []),
+    'get_token_metadata_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_26],
+        [],
+      ),
+    'get_token_price' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_7],
+        ['query'],
+      ),
     'get_token_price_by_symbol' : IDL.Func(
-        [IDL.Text, IDL.Text],
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_7],
+        ['query'],
+      ),
+    'get_token_price_by_symbol_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
         [Result_7],
         [],
       ),
-    'get_usd_value' : IDL.Func(
-        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat8],
+    'get_token_price_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_7],
+        [],
+      ),
+    'get_transaction' : IDL.Func([IDL.Text], [Result_15], ['query']),
+    'get_transaction_status' : IDL.Func([IDL.Text], [Result_16], ['query']),
+    'get_twap' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Opt(IDL.Nat32), IDL.Opt(IDL.Text)],
+        [Result_23],
+        ['query'],
+      ),
+    'get_twap_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Opt(IDL.Nat32), IDL.Opt(IDL.Text)],
+        [Result_23],
+        [],
+      ),
+    'get_usd_value_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat8, IDL.Opt(IDL.Text)],
         [Result_8],
         [],
       ),
-    'remove_asset' : IDL.Func([RemoveAssetArgs], [Result], []),
-    'safe_get_price' : IDL.Func([IDL.Text, IDL.Text], [Result_9], []),
+    'get_wallet_value_live' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Principal), IDL.Opt(IDL.Text)],
+        [Result_27],
+        [],
+      ),
+    'refresh_cached_prices' : IDL.Func([], [Result_11], []),
+    'remove_asset' : IDL.Func([RemoveAssetArgs], [Result_14], []),
+    'remove_assets' : IDL.Func([IDL.Vec(RemoveAssetArgs)], [Result_29], []),
+    'remove_network' : IDL.Func([IDL.Text], [Result_11], []),
+    'resync_nonce' : IDL.Func([IDL.Opt(IDL.Text)], [Result_10], []),
+    'safe_get_price' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_9],
+        ['query'],
+      ),
+    'safe_get_price_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result_9],
+        [],
+      ),
+    'set_chain_id' : IDL.Func([IDL.Nat64, IDL.Opt(IDL.Text)], [Result_11], []),
+    'set_confirmations' : IDL.Func(
+        [IDL.Nat64, IDL.Opt(IDL.Text)],
+        [Result_11],
+        [],
+      ),
+    'set_consensus' : IDL.Func(
+        [IDL.Opt(ConsensusConfig), IDL.Opt(IDL.Text)],
+        [Result_11],
+        [],
+      ),
+    'set_default_network' : IDL.Func([IDL.Text], [Result_11], []),
+    'set_price_refresh_config' : IDL.Func(
+        [PriceRefreshConfig],
+        [Result_11],
+        [],
+      ),
+    'set_registry_address' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Text)],
+        [Result],
+        [],
+      ),
+    'set_rpc_service' : IDL.Func(
+        [RpcService, IDL.Opt(IDL.Text)],
+        [Result_11],
+        [],
+      ),
+    'simulate_add_asset' : IDL.Func([AddAssetArgs], [Result_17], []),
+    'simulate_remove_asset' : IDL.Func([RemoveAssetArgs], [Result_17], []),
+    'speed_up_transaction' : IDL.Func([IDL.Text, IDL.Float64], [Result_14], []),
+    'update_symbol' : IDL.Func([UpdateSymbolArgs], [Result_14], []),
+    'validate_price_feed_live' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Nat64), IDL.Opt(IDL.Text)],
+        [Result_28],
+        [],
+      ),
+    'value_portfolio_live' : IDL.Func(
+        [IDL.Text, IDL.Vec(Holding), IDL.Opt(IDL.Text)],
+        [Result_25],
+        [],
+      ),
+  });
+};
+export const init = ({ IDL }) => {
+  const EthSepoliaService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+    'Sepolia' : IDL.Null,
+  });
+  const L2MainnetService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'Llama' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+  });
+  const HttpHeader = IDL.Record({ 'value' : IDL.Text, 'name' : IDL.Text });
+  const RpcApi = IDL.Record({
+    'url' : IDL.Text,
+    'headers' : IDL.Opt(IDL.Vec(HttpHeader)),
+  });
+  const EthMainnetService = IDL.Variant({
+    'Alchemy' : IDL.Null,
+    'Llama' : IDL.Null,
+    'BlockPi' : IDL.Null,
+    'Cloudflare' : IDL.Null,
+    'PublicNode' : IDL.Null,
+    'Ankr' : IDL.Null,
+  });
+  const RpcService = IDL.Variant({
+    'EthSepolia' : EthSepoliaService,
+    'BaseMainnet' : L2MainnetService,
+    'Custom' : RpcApi,
+    'OptimismMainnet' : L2MainnetService,
+    'ArbitrumOne' : L2MainnetService,
+    'EthMainnet' : EthMainnetService,
+    'Provider' : IDL.Nat64,
+  });
+  const ConsensusStrategy = IDL.Variant({
+    'Equality' : IDL.Null,
+    'Threshold' : IDL.Record({ 'min' : IDL.Nat8 }),
+  });
+  const ConsensusConfig = IDL.Record({
+    'rpc_services' : IDL.Vec(RpcService),
+    'strategy' : ConsensusStrategy,
+  });
+  const NetworkConfig = IDL.Record({
+    'confirmations' : IDL.Opt(IDL.Nat64),
+    'rpc_service' : RpcService,
+    'registry_address' : IDL.Text,
+    'chain_id' : IDL.Nat64,
+    'consensus' : IDL.Opt(ConsensusConfig),
+  });
+  const InitArgs = IDL.Record({
+    'networks' : IDL.Opt(IDL.Vec(IDL.Tuple(IDL.Text, NetworkConfig))),
+    'rpc_service' : IDL.Opt(RpcService),
+    'registry_address' : IDL.Opt(IDL.Text),
+    'chain_id' : IDL.Opt(IDL.Nat64),
+    'default_network' : IDL.Opt(IDL.Text),
   });
+  return [IDL.Opt(InitArgs)];
 };
-export const init = ({ IDL }) => { return []; };
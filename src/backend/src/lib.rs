@@ -1,25 +1,55 @@
+mod memory;
 mod service;
 mod utils;
 
 use service::get_all_assets::{AssetInfo};
 use service::get_all_assets_with_prices::{AssetWithPrice};
-use service::convert_tokens::{ConversionResult};
+use service::convert_tokens::{ConversionResult, TokenConversionResult};
 use service::safe_get_price::{PriceInfo};
 use service::get_asset_by_symbol::{AssetInfoSymbol};
 use service::get_price_feed_details::{PriceFeedDetails};
+use service::get_feed_price::{FeedInfo, FeedPrice};
 use service::get_token_price::{TokenPriceResult};
 use service::get_token_amount::{TokenAmountResult};
 use service::get_token_price_by_symbol::{TokenPriceResultSymbol};
 use service::get_usd_value::{UsdValueResult};
-use service::add_remove_asset::add_asset::{AddAssetArgs};
+use service::get_twap::{TwapResult};
+use service::add_remove_asset::add_asset::{AddAssetArgs, AddAssetResult};
 use service::add_remove_asset::remove_asset::{RemoveAssetArgs};
+use service::add_remove_asset::update_symbol::{UpdateSymbolArgs};
+use service::price_cache::{CachedAssetPrice};
+use service::value_portfolio::{Holding, PortfolioValuation};
+use service::get_wallet_value::{TokenMetadataResult, WalletValuation};
+use utils::error::BackendError;
+use utils::feed_validation::FeedValidationReport;
+use utils::helper::RoundingMode;
+use utils::config::{apply_init_args, CanisterConfig, ConsensusConfig, InitArgs, NetworkConfig};
+use utils::price_cache::{start_refresh_timer, CachedPrices, PriceRefreshConfig};
+use utils::price_history::{Candle, PricePoint};
+use utils::tx_submitter::{SimulationResult, TransactionSubmission};
+use utils::tx_tracker::{start_receipt_polling, TrackedTransaction, TransactionStatus};
 
 use candid::{ Principal};
-use ic_cdk::export_candid;
-use alloy::primitives::{address, Address};
+use ic_cdk::{export_candid, init, post_upgrade};
+use alloy::transports::icp::RpcService;
 
-pub const ASSET_REGISTRY_CONTRACT: Address = address!("e1006413d1ae924056a602D5266e86dd2570Ad68");
+#[init]
+fn init(args: Option<InitArgs>) {
+    if let Err(e) = apply_init_args(args.unwrap_or_default()) {
+        ic_cdk::trap(&e.to_string());
+    }
+    start_refresh_timer();
+    start_receipt_polling();
+}
 
+#[post_upgrade]
+fn post_upgrade(args: Option<InitArgs>) {
+    if let Err(e) = apply_init_args(args.unwrap_or_default()) {
+        ic_cdk::trap(&e.to_string());
+    }
+    start_refresh_timer();
+    start_receipt_polling();
+}
 
 
 export_candid!();
\ No newline at end of file
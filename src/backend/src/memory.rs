@@ -0,0 +1,28 @@
+use std::cell::RefCell;
+
+use ic_stable_structures::{
+    memory_manager::{MemoryId, MemoryManager, VirtualMemory},
+    DefaultMemoryImpl,
+};
+
+pub type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Each stable structure gets its own virtual memory. Never reuse or renumber an id,
+// data written under it survives upgrades.
+// 0 is retired, it held nonces keyed by address alone before they were kept per chain
+pub const CONFIG_MEMORY_ID: MemoryId = MemoryId::new(1);
+pub const PRICE_REFRESH_CONFIG_MEMORY_ID: MemoryId = MemoryId::new(2);
+pub const PRICE_CACHE_MEMORY_ID: MemoryId = MemoryId::new(3);
+pub const TRANSACTIONS_MEMORY_ID: MemoryId = MemoryId::new(4);
+pub const PRICE_HISTORY_MEMORY_ID: MemoryId = MemoryId::new(5);
+pub const ADDRESS_NONCES_MEMORY_ID: MemoryId = MemoryId::new(6);
+pub const PRICE_HISTORY_LENGTHS_MEMORY_ID: MemoryId = MemoryId::new(7);
+
+thread_local! {
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+}
+
+pub fn get_memory(id: MemoryId) -> Memory {
+    MEMORY_MANAGER.with_borrow(|manager| manager.get(id))
+}
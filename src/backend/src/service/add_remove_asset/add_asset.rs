@@ -1,131 +1,282 @@
-use crate::utils::helper::{
-    auth_guard, create_derivation_path, get_ecdsa_key_name, get_rpc_service, AssetPriceRegistry,
-};
-use alloy::{
-    network::{EthereumWallet, TransactionBuilder},
-    primitives::Address,
-    providers::{Provider, ProviderBuilder},
-    signers::{icp::IcpSigner, Signer},
-    transports::icp::IcpConfig,
+use crate::utils::helper::{auth_guard, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::erc20::token_metadata;
+use crate::utils::error::BackendError;
+use crate::utils::fee_oracle::FeeOptions;
+use crate::utils::feed_validation::validate_feed;
+use crate::utils::price_feed::{feed_metadata, FeedMetadata};
+use crate::utils::tx_submitter::{
+    resolve_batch_network, simulate_call, submit_registry_batch, submit_registry_call,
+    SimulationResult, TransactionSubmission,
 };
+use alloy::primitives::Address;
 use candid::CandidType;
-use ic_cdk::{api::caller, update};
+use ic_cdk::update;
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, collections::HashMap, str::FromStr};
-
-use crate::ASSET_REGISTRY_CONTRACT;
-
-thread_local! {
-    static ADDRESS_NONCES: RefCell<HashMap<Address, u64>> = RefCell::new(HashMap::new());
-}
+use std::str::FromStr;
 
 #[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
 pub struct AddAssetArgs {
     pub asset_address: String,
     pub price_feed: String,
-    pub token_decimals: u8,
+    /// Read from the token's `decimals()` when omitted
+    pub token_decimals: Option<u8>,
     pub stale_price_threshold: u64,
-    pub symbol: String,
+    /// Read from the token's `symbol()` when omitted
+    pub symbol: Option<String>,
+    /// Check the given decimals and symbol against the token contract and refuse on a
+    /// mismatch. Always done for fields that are omitted.
+    pub detect_metadata: Option<bool>,
+    /// Register even when `validate_price_feed_live` reports errors
+    pub override_feed_validation: Option<bool>,
+    pub network: Option<String>,
+    pub fee: Option<FeeOptions>,
+}
+
+/// The submitted transaction and the feed validation warnings it was sent despite
+#[derive(CandidType, Clone, Debug, Deserialize)]
+pub struct AddAssetResult {
+    pub submission: TransactionSubmission,
+    pub feed_warnings: Vec<String>,
+}
+
+#[update]
+async fn add_asset(args: AddAssetArgs) -> Result<AddAssetResult, BackendError> {
+    // Auth
+    auth_guard()?;
+    let (network, call, feed_warnings) = prepare_add_asset(&args).await?;
+    let arguments = recorded_arguments(&args, &call);
+
+    let fee = args.fee.unwrap_or_default();
+    let submission = submit_registry_call(&network, "add_asset", arguments, call, &fee).await?;
+    Ok(AddAssetResult {
+        submission,
+        feed_warnings,
+    })
 }
 
+/// `add_asset` for up to 20 assets on one network, sent with consecutive nonces.
+/// Returns one result per item, in order, see `submit_registry_batch`.
 #[update]
-async fn add_asset(args: AddAssetArgs) -> Result<String, String> {
+async fn add_assets(
+    args: Vec<AddAssetArgs>,
+) -> Result<Vec<Result<AddAssetResult, BackendError>>, BackendError> {
     // Auth
     auth_guard()?;
+    let network = resolve_batch_network(args.iter().map(|item| item.network.clone()).collect())?;
+
+    let mut calls = Vec::with_capacity(args.len());
+    let mut warnings = Vec::with_capacity(args.len());
+    let mut seen: Vec<(Address, String)> = Vec::new();
+    for item in args {
+        let (prepared, feed_warnings) = match prepare_add_asset(&item).await {
+            Ok((_, call, feed_warnings)) => {
+                if is_duplicate(&seen, call.assetAddress, &call.symbol) {
+                    // The registry would accept the first and revert the second once mined
+                    let error = BackendError::InvalidInput(format!(
+                        "Asset {:?} or symbol {} appears twice in the batch",
+                        call.assetAddress, call.symbol
+                    ));
+                    (Err(error), Vec::new())
+                } else {
+                    seen.push((call.assetAddress, call.symbol.to_lowercase()));
+                    let arguments = recorded_arguments(&item, &call);
+                    (Ok((arguments, call, item.fee.unwrap_or_default())), feed_warnings)
+                }
+            }
+            Err(e) => (Err(e), Vec::new()),
+        };
+        calls.push(prepared);
+        warnings.push(feed_warnings);
+    }
+
+    let results = submit_registry_batch(&network, "add_assets", calls).await?;
+    Ok(results
+        .into_iter()
+        .zip(warnings)
+        .map(|(result, feed_warnings)| {
+            result.map(|submission| AddAssetResult {
+                submission,
+                feed_warnings,
+            })
+        })
+        .collect())
+}
+
+/// Whether an earlier item of the batch has the same asset, or the same symbol in any
+/// case. `seen` holds lowercase symbols.
+fn is_duplicate(seen: &[(Address, String)], asset: Address, symbol: &str) -> bool {
+    let symbol = symbol.to_lowercase();
+    seen.iter()
+        .any(|(seen_asset, seen_symbol)| *seen_asset == asset || *seen_symbol == symbol)
+}
+
+/// Dry run of `add_asset`: reports whether the registry would accept it and what it would cost
+#[update]
+async fn simulate_add_asset(args: AddAssetArgs) -> Result<SimulationResult, BackendError> {
+    // Auth
+    auth_guard()?;
+    let (network, call, _) = prepare_add_asset(&args).await?;
+
+    let fee = args.fee.unwrap_or_default();
+    simulate_call(&network, network.registry_address, call, &fee).await
+}
+
+// Record the registered values, including detected ones
+fn recorded_arguments(args: &AddAssetArgs, call: &AssetPriceRegistry::addAssetCall) -> String {
+    format!(
+        "{:?}",
+        AddAssetArgs {
+            token_decimals: Some(call.tokenDecimals),
+            symbol: Some(call.symbol.clone()),
+            ..args.clone()
+        }
+    )
+}
 
-    // Setup signer 
-    let caller_principal = caller();
-    let ecdsa_key_name = get_ecdsa_key_name();
-    let derivation_path = create_derivation_path(&caller_principal);
-    let signer = IcpSigner::new(derivation_path, &ecdsa_key_name, None)
-        .await
-        .map_err(|e| format!("Failed to create signer: {}", e))?;
-
-    let from_address: Address = signer.address();
-
-    // Setup provider
-    let wallet = EthereumWallet::from(signer);
-    let rpc_service = get_rpc_service();
-    let config = IcpConfig::new(rpc_service);
-    let provider = ProviderBuilder::new()
-        .with_gas_estimation()
-        .wallet(wallet)
-        .on_icp(config);
+/// The network, the registry call and the feed validation warnings, which do not block
+async fn prepare_add_asset(
+    args: &AddAssetArgs,
+) -> Result<(Network, AssetPriceRegistry::addAssetCall, Vec<String>), BackendError> {
+    let network = resolve_network(args.network.clone())?;
 
     // Parse 
     let asset_address = Address::from_str(&args.asset_address)
-        .map_err(|e| format!("Invalid asset address: {}", e))?;
+        .map_err(|e| BackendError::InvalidAddress(format!("asset address: {}", e)))?;
     let price_feed = Address::from_str(&args.price_feed)
-        .map_err(|e| format!("Invalid price feed address: {}", e))?;
+        .map_err(|e| BackendError::InvalidAddress(format!("price feed address: {}", e)))?;
 
     // Validate
-    if args.symbol.is_empty() {
-        return Err("Symbol cannot be empty".to_string());
+    if args.symbol.as_deref() == Some("") {
+        return Err(BackendError::InvalidInput("Symbol cannot be empty".to_string()));
     }
     if args.stale_price_threshold == 0 {
-        return Err("Stale price threshold must be greater than 0".to_string());
+        return Err(BackendError::InvalidInput(
+            "Stale price threshold must be greater than 0".to_string(),
+        ));
     }
-
-    // Get nonce 
-    let maybe_nonce =
-        ADDRESS_NONCES.with_borrow(|nonces| nonces.get(&from_address).map(|nonce| nonce + 1));
-
-    let nonce = if let Some(nonce) = maybe_nonce {
-        nonce
+    let detect = args.detect_metadata.unwrap_or(false)
+        || args.token_decimals.is_none()
+        || args.symbol.is_none();
+    let validate = !args.override_feed_validation.unwrap_or(false);
+    // Read once for both the detection and the validation
+    let feed = if detect || validate {
+        Some(feed_metadata(&network, price_feed).await?)
     } else {
-        provider
-            .get_transaction_count(from_address)
-            .await
-            .unwrap_or(0)
+        None
     };
+    let (token_decimals, symbol) =
+        resolve_metadata(&network, args, asset_address, price_feed, feed.as_ref()).await?;
 
-    // Contract Call
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, &provider);
+    let mut feed_warnings = Vec::new();
+    if let (true, Some(feed)) = (validate, &feed) {
+        let report = validate_feed(
+            &network,
+            price_feed,
+            feed,
+            &symbol,
+            args.stale_price_threshold,
+        )
+        .await?;
+        if !report.passed {
+            return Err(BackendError::InvalidInput(format!(
+                "Price feed validation failed: {}. Set override_feed_validation to register anyway",
+                report.errors.join("; ")
+            )));
+        }
+        feed_warnings = report.warnings;
+    }
 
     // Transaction call
-    let call_builder = contract.addAsset(
-        asset_address,
-        price_feed,
+    let call = AssetPriceRegistry::addAssetCall {
+        assetAddress: asset_address,
+        priceFeed: price_feed,
+        tokenDecimals: token_decimals,
+        stalePriceThresholdInSeconds: args.stale_price_threshold,
+        symbol,
+    };
+    Ok((network, call, feed_warnings))
+}
+
+/// Token decimals and symbol to register. Given values are used as they are unless
+/// `detect_metadata` is set; omitted ones are read from the token, after checking that
+/// `price_feed` answers `decimals()` and `description()` like a Chainlink aggregator.
+async fn resolve_metadata(
+    network: &Network,
+    args: &AddAssetArgs,
+    asset_address: Address,
+    price_feed: Address,
+    feed: Option<&FeedMetadata>,
+) -> Result<(u8, String), BackendError> {
+    if let (Some(token_decimals), Some(symbol), false) = (
         args.token_decimals,
-        args.stale_price_threshold,
-        args.symbol,
-    );
-
-    // Get the transaction request 
-    let mut tx_request = call_builder.into_transaction_request();
-
-    tx_request = tx_request
-        .with_nonce(nonce)
-        .with_gas_limit(500_000)
-        .with_chain_id(11155111); // Sepolia testnet, adjust as needed
-
-    // Estimate gas fees
-    let gas_price = provider
-        .get_gas_price()
-        .await
-        .map_err(|e| format!("Failed to get gas price: {}", e))?;
-
-    // For EIP-1559 transactions, set max_fee_per_gas and max_priority_fee_per_gas
-    tx_request = tx_request
-        .with_max_fee_per_gas(gas_price * 2) // 2x current gas price as max fee
-        .with_max_priority_fee_per_gas(gas_price / 10); // 10% of gas price as priority fee
-
-    // Send the transaction
-    match provider.send_transaction(tx_request).await {
-        Ok(pending_tx_builder) => {
-            let tx_hash = *pending_tx_builder.tx_hash();
-            let tx_response = provider.get_transaction_by_hash(tx_hash).await.unwrap();
-
-            match tx_response {
-                Some(tx) => {
-                    ADDRESS_NONCES.with_borrow_mut(|nonces| {
-                        nonces.insert(from_address, tx.nonce);
-                    });
-                    Ok(format!("Transaction hash: {:?}", tx_hash))
-                }
-                None => Err("Could not get transaction.".to_string()),
-            }
+        &args.symbol,
+        args.detect_metadata.unwrap_or(false),
+    ) {
+        return Ok((token_decimals, symbol.clone()));
+    }
+
+    if !feed.is_some_and(|feed| feed.decimals.is_some() && feed.description.is_some()) {
+        return Err(BackendError::InvalidInput(format!(
+            "{:?} is not a Chainlink price feed: decimals() or description() reverted",
+            price_feed
+        )));
+    }
+
+    // Tokens like MKR return bytes32 from symbol(), those need an explicit symbol
+    let metadata = token_metadata(network, asset_address).await.map_err(|e| {
+        BackendError::InvalidInput(format!(
+            "Could not read decimals() and symbol() of {:?}, pass token_decimals and symbol: {}",
+            asset_address, e
+        ))
+    })?;
+    if let Some(token_decimals) = args.token_decimals {
+        if token_decimals != metadata.decimals {
+            return Err(BackendError::InvalidInput(format!(
+                "token_decimals {} does not match decimals() {} of {:?}",
+                token_decimals, metadata.decimals, asset_address
+            )));
+        }
+    }
+    if let Some(symbol) = &args.symbol {
+        if !symbol.eq_ignore_ascii_case(&metadata.symbol) {
+            return Err(BackendError::InvalidInput(format!(
+                "Symbol {} does not match symbol() {} of {:?}",
+                symbol, metadata.symbol, asset_address
+            )));
         }
-        Err(e) => Err(format!("Failed to add asset: {}", e)),
+    }
+    if metadata.symbol.is_empty() {
+        return Err(BackendError::InvalidInput(format!(
+            "{:?} returns an empty symbol(), pass symbol",
+            asset_address
+        )));
+    }
+
+    Ok((
+        args.token_decimals.unwrap_or(metadata.decimals),
+        args.symbol.clone().unwrap_or(metadata.symbol),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_asset_or_symbol_in_batch() {
+        let seen = vec![(Address::repeat_byte(1), "weth".to_string())];
+
+        assert!(is_duplicate(&seen, Address::repeat_byte(1), "OTHER"));
+        assert!(is_duplicate(&seen, Address::repeat_byte(2), "weth"));
+        assert!(!is_duplicate(&seen, Address::repeat_byte(2), "WBTC"));
+    }
+
+    #[test]
+    fn duplicate_symbol_ignores_case() {
+        let seen = vec![(Address::repeat_byte(1), "WETH".to_lowercase())];
+
+        assert!(is_duplicate(&seen, Address::repeat_byte(2), "WETH"));
+        assert!(is_duplicate(&seen, Address::repeat_byte(2), "wEtH"));
     }
 }
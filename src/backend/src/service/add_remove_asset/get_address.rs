@@ -2,9 +2,10 @@ use alloy::signers::{icp::IcpSigner, Signer};
 use candid::Principal;
 
 use crate::utils::helper::{auth_guard, create_derivation_path, get_ecdsa_key_name};
+use crate::utils::error::BackendError;
 
 #[ic_cdk::update]
-async fn get_address(principal: Option<Principal>) -> Result<String, String> {
+async fn get_address(principal: Option<Principal>) -> Result<String, BackendError> {
 
     auth_guard()?;
 
@@ -16,7 +17,7 @@ async fn get_address(principal: Option<Principal>) -> Result<String, String> {
     let derivation_path = create_derivation_path(&principal);
     let signer = IcpSigner::new(derivation_path, &ecdsa_key_name, None)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| BackendError::SignerError(e.to_string()))?;
 
     let address = signer.address();
     Ok(address.to_string())
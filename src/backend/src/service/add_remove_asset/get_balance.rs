@@ -1,4 +1,6 @@
-use crate::utils::helper::{create_derivation_path, get_ecdsa_key_name, get_rpc_service};
+use crate::utils::helper::{create_derivation_path, get_ecdsa_key_name};
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
 use alloy::{
     providers::{Provider, ProviderBuilder},
     signers::{icp::IcpSigner, Signer},
@@ -10,9 +12,10 @@ use crate::utils::helper::{
 };
 
 #[ic_cdk::update]
-async fn get_balance(principal: Option<Principal>) -> Result<String, String> {
+async fn get_balance_live(principal: Option<Principal>, network: Option<String>) -> Result<String, BackendError> {
 
     auth_guard()?;
+    let network = resolve_network(network)?;
 
     // If no principal is specified in call, attempt to use caller principal
     let principal = principal.unwrap_or_else(ic_cdk::caller);
@@ -22,11 +25,10 @@ async fn get_balance(principal: Option<Principal>) -> Result<String, String> {
     let derivation_path = create_derivation_path(&principal);
     let signer = IcpSigner::new(derivation_path, &ecdsa_key_name, None)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| BackendError::SignerError(e.to_string()))?;
 
     // Setup provider
-    let rpc_service = get_rpc_service();
-    let config = IcpConfig::new(rpc_service);
+    let config = IcpConfig::new(network.rpc_service);
     let provider = ProviderBuilder::new().on_icp(config);
 
     // Get balance for signer address
@@ -35,6 +37,6 @@ async fn get_balance(principal: Option<Principal>) -> Result<String, String> {
 
     match result {
         Ok(balance) => Ok(balance.to_string()),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(BackendError::from_call_error(e)),
     }
 }
\ No newline at end of file
@@ -1,119 +1,97 @@
-use std::{cell::RefCell, collections::HashMap, str::FromStr};
-use alloy::{
-    network::{EthereumWallet, TransactionBuilder},
-    primitives::{Address},
-    providers::{Provider, ProviderBuilder},
-    signers::{icp::IcpSigner, Signer},
-    transports::icp::IcpConfig,
-};
+use std::str::FromStr;
+use alloy::primitives::Address;
 use candid::CandidType;
-use ic_cdk::{api::caller, update};
+use ic_cdk::update;
 use serde::{Deserialize, Serialize};
 use crate::utils::helper::{
     auth_guard,
-    create_derivation_path,
-    get_ecdsa_key_name,
-    get_rpc_service,
     AssetPriceRegistry
 };
-use crate::ASSET_REGISTRY_CONTRACT;
-
-
-thread_local! {
-    static ADDRESS_NONCES: RefCell<HashMap<Address, u64>> = RefCell::new(HashMap::new());
-}
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::error::BackendError;
+use crate::utils::fee_oracle::FeeOptions;
+use crate::utils::tx_submitter::{
+    resolve_batch_network, simulate_call, submit_registry_batch, submit_registry_call,
+    SimulationResult, TransactionSubmission,
+};
 
 #[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
 pub struct RemoveAssetArgs {
     pub asset_address: String,
+    pub network: Option<String>,
+    pub fee: Option<FeeOptions>,
 }
 
 #[update]
-pub async fn remove_asset(args: RemoveAssetArgs) -> Result<String, String> {
+pub async fn remove_asset(args: RemoveAssetArgs) -> Result<TransactionSubmission, BackendError> {
     // auth
     auth_guard()?;
-    
-    // Setup signer
-    let caller_principal = caller();
-    let ecdsa_key_name = get_ecdsa_key_name();
-    let derivation_path = create_derivation_path(&caller_principal);
-    let signer = IcpSigner::new(derivation_path, &ecdsa_key_name, None)
-        .await
-        .map_err(|e| format!("Failed to create signer: {}", e))?;
-    
-    let from_address = signer.address();
-    
-    // Setup provider 
-    let wallet = EthereumWallet::from(signer);
-    let rpc_service = get_rpc_service();
-    let config = IcpConfig::new(rpc_service);
-    let provider = ProviderBuilder::new()
-        .with_gas_estimation()
-        .wallet(wallet)
-        .on_icp(config);
-    
-    // Parse 
-    let asset_address = Address::from_str(&args.asset_address)
-        .map_err(|e| format!("Invalid asset address: {}", e))?;
+    let (network, call) = prepare_remove_asset(&args)?;
+    let arguments = format!("{:?}", args);
+
+    let fee = args.fee.unwrap_or_default();
+    submit_registry_call(&network, "remove_asset", arguments, call, &fee).await
+}
+
+/// `remove_asset` for up to 20 assets on one network, sent with consecutive nonces.
+/// Returns one result per item, in order, see `submit_registry_batch`.
+#[update]
+async fn remove_assets(
+    args: Vec<RemoveAssetArgs>,
+) -> Result<Vec<Result<TransactionSubmission, BackendError>>, BackendError> {
+    // auth
+    auth_guard()?;
+    let network = resolve_batch_network(args.iter().map(|item| item.network.clone()).collect())?;
+
+    let mut seen: Vec<Address> = Vec::new();
+    let calls = args
+        .into_iter()
+        .map(|item| match prepare_remove_asset(&item) {
+            Ok((_, call)) if seen.contains(&call.assetAddress) => Err(BackendError::InvalidInput(
+                format!("Asset {:?} appears twice in the batch", call.assetAddress),
+            )),
+            Ok((_, call)) => {
+                seen.push(call.assetAddress);
+                let arguments = format!("{:?}", item);
+                Ok((arguments, call, item.fee.unwrap_or_default()))
+            }
+            Err(e) => Err(e),
+        })
+        .collect();
+
+    submit_registry_batch(&network, "remove_assets", calls).await
+}
+
+/// Dry run of `remove_asset`: reports whether the registry would accept it and what it would cost
+#[update]
+async fn simulate_remove_asset(args: RemoveAssetArgs) -> Result<SimulationResult, BackendError> {
+    // auth
+    auth_guard()?;
+    let (network, call) = prepare_remove_asset(&args)?;
+
+    let fee = args.fee.unwrap_or_default();
+    simulate_call(&network, network.registry_address, call, &fee).await
+}
+
+fn prepare_remove_asset(
+    args: &RemoveAssetArgs,
+) -> Result<(Network, AssetPriceRegistry::removeAssetCall), BackendError> {
+    let network = resolve_network(args.network.clone())?;
     
     // Validate
     if args.asset_address.is_empty() {
-        return Err("Asset address cannot be empty".to_string());
+        return Err(BackendError::InvalidInput(
+            "Asset address cannot be empty".to_string(),
+        ));
     }
-    
-    // Get nonce
-    let maybe_nonce = ADDRESS_NONCES.with_borrow(|nonces| {
-        nonces.get(&from_address).map(|nonce| nonce + 1)
-    });
-    
-    let nonce = if let Some(nonce) = maybe_nonce {
-        nonce
-    } else {
-        provider
-            .get_transaction_count(from_address)
-            .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?
-    };
+
+    // Parse 
+    let asset_address = Address::from_str(&args.asset_address)
+        .map_err(|e| BackendError::InvalidAddress(format!("asset address: {}", e)))?;
     
     //call contract
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, &provider);
-    let call_builder = contract.removeAsset(asset_address);
-    
-    // Get the transaction request
-    let mut tx_request = call_builder.into_transaction_request();
-    
-    // Set required transaction parameters
-    tx_request = tx_request
-        .with_nonce(nonce)
-        .with_gas_limit(200_000) 
-        .with_chain_id(11155111); 
-    
-    // Estimate gas fees
-    let gas_price = provider
-        .get_gas_price()
-        .await
-        .map_err(|e| format!("Failed to get gas price: {}", e))?;
-    
-    tx_request = tx_request
-        .with_max_fee_per_gas(gas_price * 2)
-        .with_max_priority_fee_per_gas(gas_price / 10); 
-    
-    // Send the transaction
-    match provider.send_transaction(tx_request).await {
-        Ok(pending_tx_builder) => {
-            let tx_hash = *pending_tx_builder.tx_hash();
-            let tx_response = provider.get_transaction_by_hash(tx_hash).await.unwrap();
-
-            match tx_response {
-                Some(tx) => {
-                    ADDRESS_NONCES.with_borrow_mut(|nonces| {
-                        nonces.insert(from_address, tx.nonce);
-                    });
-                    Ok(format!("Asset removed successfully. Transaction hash: {:?}", tx_hash))
-                }
-                None => Err("Could not get transaction.".to_string()),
-            }
-        }
-        Err(e) => Err(format!("Failed to remove asset: {}", e)),
-    }
-}
\ No newline at end of file
+    let call = AssetPriceRegistry::removeAssetCall {
+        assetAddress: asset_address,
+    };
+    Ok((network, call))
+}
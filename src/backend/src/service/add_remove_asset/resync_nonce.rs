@@ -0,0 +1,21 @@
+use alloy::signers::Signer;
+
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::auth_guard;
+use crate::utils::nonce_manager::resync_nonce as resync_address_nonce;
+use crate::utils::tx_submitter::create_signer;
+
+/// Re-read the transaction count of the caller's derived address and reset the
+/// cached nonce to it. Use when transactions were sent outside the canister.
+#[ic_cdk::update]
+async fn resync_nonce(network: Option<String>) -> Result<u64, BackendError> {
+
+    auth_guard()?;
+    let network = resolve_network(network)?;
+
+    // Setup signer
+    let signer = create_signer(&ic_cdk::caller()).await?;
+
+    resync_address_nonce(&network, signer.address()).await
+}
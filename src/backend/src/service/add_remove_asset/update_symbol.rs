@@ -0,0 +1,48 @@
+use std::str::FromStr;
+use alloy::primitives::Address;
+use candid::CandidType;
+use ic_cdk::update;
+use serde::{Deserialize, Serialize};
+use crate::utils::helper::{
+    auth_guard,
+    AssetPriceRegistry
+};
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::fee_oracle::FeeOptions;
+use crate::utils::tx_submitter::{submit_registry_call, TransactionSubmission};
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct UpdateSymbolArgs {
+    pub asset_address: String,
+    pub symbol: String,
+    pub network: Option<String>,
+    pub fee: Option<FeeOptions>,
+}
+
+/// Rename an asset in place, keeping its position in the owner's asset list
+#[update]
+async fn update_symbol(args: UpdateSymbolArgs) -> Result<TransactionSubmission, BackendError> {
+    // auth
+    auth_guard()?;
+    let network = resolve_network(args.network.clone())?;
+    let arguments = format!("{:?}", args);
+
+    // Parse
+    let asset_address = Address::from_str(&args.asset_address)
+        .map_err(|e| BackendError::InvalidAddress(format!("asset address: {}", e)))?;
+
+    // Validate
+    if args.symbol.is_empty() {
+        return Err(BackendError::InvalidInput("Symbol cannot be empty".to_string()));
+    }
+
+    // call contract
+    let call = AssetPriceRegistry::updateSymbolCall {
+        assetAddress: asset_address,
+        newSymbol: args.symbol,
+    };
+
+    let fee = args.fee.unwrap_or_default();
+    submit_registry_call(&network, "update_symbol", arguments, call, &fee).await
+}
@@ -0,0 +1,144 @@
+use alloy::transports::icp::RpcService;
+use ic_cdk::{query, update};
+
+use crate::utils::config::{
+    get_config as get_stored_config, resolve_network, unknown_network, update_config,
+    validate_confirmations, validate_consensus, validate_network, CanisterConfig,
+    ConsensusConfig, NetworkConfig,
+};
+use crate::utils::error::BackendError;
+use crate::utils::helper::{controller_guard, validate_and_normalize_address};
+
+#[query]
+fn get_config() -> CanisterConfig {
+    get_stored_config()
+}
+
+/// Add a network, or replace the network with the same name
+#[update]
+fn add_network(name: String, network: NetworkConfig) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(BackendError::InvalidInput(
+            "Network name cannot be empty".to_string(),
+        ));
+    }
+    let network = validate_network(network)?;
+    update_config(|config| {
+        config.networks.insert(name, network);
+        Ok(())
+    })
+}
+
+#[update]
+fn remove_network(name: String) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    let name = name.trim().to_lowercase();
+    update_config(|config| {
+        if config.default_network == name {
+            return Err(BackendError::InvalidInput(
+                "Cannot remove the default network".to_string(),
+            ));
+        }
+        config
+            .networks
+            .remove(&name)
+            .map(|_| ())
+            .ok_or_else(|| unknown_network(&name))
+    })
+}
+
+#[update]
+fn set_default_network(name: String) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    let network = resolve_network(Some(name))?;
+    update_config(|config| {
+        config.default_network = network.name;
+        Ok(())
+    })
+}
+
+#[update]
+fn set_rpc_service(rpc_service: RpcService, network: Option<String>) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    update_network(network, |entry| {
+        entry.rpc_service = rpc_service;
+        Ok(())
+    })
+}
+
+#[update]
+fn set_registry_address(
+    registry_address: String,
+    network: Option<String>,
+) -> Result<String, BackendError> {
+    controller_guard()?;
+
+    let registry_address = format!("{:?}", validate_and_normalize_address(&registry_address)?);
+    update_network(network, |entry| {
+        entry.registry_address = registry_address.clone();
+        Ok(())
+    })?;
+    Ok(registry_address)
+}
+
+#[update]
+fn set_chain_id(chain_id: u64, network: Option<String>) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    if chain_id == 0 {
+        return Err(BackendError::InvalidInput(
+            "Chain id must be greater than 0".to_string(),
+        ));
+    }
+    update_network(network, |entry| {
+        entry.chain_id = chain_id;
+        Ok(())
+    })
+}
+
+/// Number of confirmations before a tracked transaction is reported as final
+#[update]
+fn set_confirmations(confirmations: u64, network: Option<String>) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    validate_confirmations(confirmations)?;
+    update_network(network, |entry| {
+        entry.confirmations = Some(confirmations);
+        Ok(())
+    })
+}
+
+/// Cross-check price and conversion reads on a network against more providers.
+/// `None` goes back to reading from the network's RPC service alone.
+#[update]
+fn set_consensus(consensus: Option<ConsensusConfig>, network: Option<String>) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    if let Some(consensus) = &consensus {
+        validate_consensus(consensus)?;
+    }
+    update_network(network, |entry| {
+        entry.consensus = consensus;
+        Ok(())
+    })
+}
+
+fn update_network(
+    selector: Option<String>,
+    f: impl FnOnce(&mut NetworkConfig) -> Result<(), BackendError>,
+) -> Result<(), BackendError> {
+    let name = resolve_network(selector)?.name;
+    update_config(|config| {
+        let entry = config
+            .networks
+            .get_mut(&name)
+            .ok_or_else(|| unknown_network(&name))?;
+        f(entry)
+    })
+}
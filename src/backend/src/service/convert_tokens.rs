@@ -1,24 +1,30 @@
 use crate::utils::helper::{
-    format_price, format_token_amount, format_usd_amount, validate_eth_address, get_rpc_service,
-    parse_token_amount, AssetPriceRegistry,
+    format_price, format_token_amount, format_usd_amount, format_with_decimals, mul_div_rounded,
+    validate_eth_address, parse_token_amount, parse_usd_value, AssetPriceRegistry, RoundingMode,
 };
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::get_cached_prices;
 use alloy::{
-    primitives::{address, Uint, I256, U256},
+    primitives::{address, Address, Uint, I256, U256},
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
 use ic_cdk::update;
 
+// Revert reason of the registry's batch reads when the amount count is off
+const LENGTH_MISMATCH: &str = "Input array length mismatch";
+
 // Result structures
-#[derive(CandidType, Deserialize)]
+#[derive(CandidType, Deserialize, PartialEq)]
 pub struct RawConversionData {
     pub amount: String,
     pub price: String,
 }
 
-#[derive(CandidType, Deserialize)]
+#[derive(CandidType, Deserialize, PartialEq)]
 pub struct ConversionResult {
     pub symbol: String,
     pub input: String,
@@ -30,49 +36,77 @@ pub struct ConversionResult {
     pub raw: RawConversionData,
 }
 
+/// One side of a token to token conversion, with the price it was converted at
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
+pub struct ConversionLeg {
+    pub asset_address: String,
+    pub symbol: String,
+    pub token_decimals: u8,
+    pub price: String,
+    pub raw_price: String,
+    pub price_decimals: u8,
+    pub last_updated_time: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TokenConversionResult {
+    pub from: ConversionLeg,
+    pub to: ConversionLeg,
+    pub input: String,
+    pub output: String,
+    pub raw_input: String,
+    pub raw_output: String,
+    pub rounding: RoundingMode,
+}
+
 // Conversion functions
 #[update]
-async fn convert_usd_to_tokens(
+async fn convert_usd_to_tokens_live(
     owner_address: String,
     usd_amounts: Vec<String>,
-) -> Result<Vec<ConversionResult>, String> {
+    network: Option<String>,
+) -> Result<Vec<ConversionResult>, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
 
     if usd_amounts.len() > 10 {
-        return Err("Maximum 10 conversions per call".to_string());
+        return Err(BackendError::InvalidInput(
+            "Maximum 10 conversions per call".to_string(),
+        ));
     }
 
-    let parsed_inputs: Result<Vec<(String, f64)>, String> = usd_amounts
+    // Same fixed-point parsing as get_token_amount, so batch and single conversions agree
+    let usd_amounts_raw: Vec<Uint<248, 4>> = usd_amounts
         .iter()
-        .map(|input| {
-            let amount_str = input.trim();
-            let parsed_amount = amount_str
-                .parse::<f64>()
-                .map_err(|_| "Invalid USD amount format".to_string())?;
-            Ok((input.clone(), parsed_amount))
-        })
-        .collect();
-    let parsed_inputs = parsed_inputs?;
+        .map(|input| parse_usd_value(input))
+        .collect::<Result<_, BackendError>>()?;
 
-    let icp_config = IcpConfig::new(get_rpc_service()).set_max_response_size(30_000);
-    let provider = ProviderBuilder::new().on_icp(icp_config);
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    let network = resolve_network(network)?;
 
-    let usd_amounts_raw: Result<Vec<Uint<248, 4>>, String> = parsed_inputs
-        .iter()
-        .map(|(_, amount)| {
-            let raw_value = (amount * 1e18) as u128;
-            Ok(Uint::<248, 4>::from(raw_value))
-        })
-        .collect();
-    let usd_amounts_raw = usd_amounts_raw?;
+    consensus_read(&network, |network| {
+        let usd_amounts = usd_amounts.clone();
+        let usd_amounts_raw = usd_amounts_raw.clone();
+        async move { fetch_usd_to_tokens(&network, owner_addr, usd_amounts, usd_amounts_raw).await }
+    })
+    .await
+}
+
+async fn fetch_usd_to_tokens(
+    network: &Network,
+    owner_addr: Address,
+    usd_amounts: Vec<String>,
+    usd_amounts_raw: Vec<Uint<248, 4>>,
+) -> Result<Vec<ConversionResult>, BackendError> {
+
+    let icp_config = IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000);
+    let provider = ProviderBuilder::new().on_icp(icp_config);
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
 
     let result = contract
         .getAllConvertUsdToToken(owner_addr, usd_amounts_raw)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
 
     let mut formatted_results = Vec::new();
     for i in 0..result._0.addresses.len() {
@@ -82,7 +116,7 @@ async fn convert_usd_to_tokens(
 
         formatted_results.push(ConversionResult {
             symbol: result._0.symbols[i].clone(),
-            input: parsed_inputs[i].0.clone(),
+            input: usd_amounts[i].clone(),
             output: format_token_amount(token_amount, token_decimals),
             price: format_price(price, result._0.priceDecimals[i]),
             price_decimals: result._0.priceDecimals[i],
@@ -98,20 +132,38 @@ async fn convert_usd_to_tokens(
 }
 
 #[update]
-async fn convert_tokens_to_usd(
+async fn convert_tokens_to_usd_live(
     owner_address: String,
     token_amounts: Vec<String>,
-) -> Result<Vec<ConversionResult>, String> {
+    network: Option<String>,
+) -> Result<Vec<ConversionResult>, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
 
     if token_amounts.len() > 10 {
-        return Err("Maximum 10 conversions per call".to_string());
+        return Err(BackendError::InvalidInput(
+            "Maximum 10 conversions per call".to_string(),
+        ));
     }
 
-    let icp_config = IcpConfig::new(get_rpc_service()).set_max_response_size(30_000);
+    let network = resolve_network(network)?;
+
+    consensus_read(&network, |network| {
+        let token_amounts = token_amounts.clone();
+        async move { fetch_tokens_to_usd(&network, owner_addr, token_amounts).await }
+    })
+    .await
+}
+
+async fn fetch_tokens_to_usd(
+    network: &Network,
+    owner_addr: Address,
+    token_amounts: Vec<String>,
+) -> Result<Vec<ConversionResult>, BackendError> {
+
+    let icp_config = IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000);
     let provider = ProviderBuilder::new().on_icp(icp_config);
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
 
     let dummy_amounts: Vec<Uint<248, 4>> = token_amounts
         .iter()
@@ -122,7 +174,7 @@ async fn convert_tokens_to_usd(
         .getAllPriceToConvertToUsd(owner_addr.clone(), dummy_amounts)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
 
     let mut actual_amounts = Vec::new();
     for i in 0..token_amounts.len() {
@@ -135,7 +187,7 @@ async fn convert_tokens_to_usd(
         .getAllPriceToConvertToUsd(owner_addr, actual_amounts)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
 
     let mut formatted_results = Vec::new();
     for i in 0..result._0.addresses.len() {
@@ -159,3 +211,152 @@ async fn convert_tokens_to_usd(
     }
     Ok(formatted_results)
 }
+
+/// Convert `amount` of `from_asset` into `to_asset` through both USD prices, in exact
+/// integer math. Rounds toward zero unless `rounding` says otherwise.
+#[update]
+async fn convert_token_to_token_live(
+    owner_address: String,
+    from_asset: String,
+    to_asset: String,
+    amount: String,
+    rounding: Option<RoundingMode>,
+    network: Option<String>,
+) -> Result<TokenConversionResult, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let from_addr = validate_eth_address(&from_asset)?;
+    let to_addr = validate_eth_address(&to_asset)?;
+    let rounding = rounding.unwrap_or_default();
+
+    let network = resolve_network(network)?;
+
+    let legs = consensus_read(&network, |network| async move {
+        fetch_asset_prices(&network, owner_addr).await
+    })
+    .await?;
+    let from = find_leg(&legs, owner_addr, from_addr)?;
+    let to = find_leg(&legs, owner_addr, to_addr)?;
+
+    let raw_input = parse_token_amount(&amount, from.token_decimals)?;
+
+    // output = input * from_price / to_price, moved from the source's token and price
+    // decimals to the target's. The power of ten goes on whichever side keeps it whole.
+    let from_scale = from.token_decimals as u32 + from.price_decimals as u32;
+    let to_scale = to.token_decimals as u32 + to.price_decimals as u32;
+    let mut numerator = positive_price(&from)?;
+    let mut denominator = positive_price(&to)?;
+    if to_scale >= from_scale {
+        numerator = numerator.checked_mul(pow10(to_scale - from_scale)?)
+            .ok_or(BackendError::Overflow("Overflow in multiplication".to_string()))?;
+    } else {
+        denominator = denominator.checked_mul(pow10(from_scale - to_scale)?)
+            .ok_or(BackendError::Overflow("Overflow in multiplication".to_string()))?;
+    }
+    let raw_output = mul_div_rounded(raw_input, numerator, denominator, rounding)?;
+
+    Ok(TokenConversionResult {
+        output: format_with_decimals(raw_output, to.token_decimals),
+        raw_input: raw_input.to_string(),
+        raw_output: raw_output.to_string(),
+        input: amount,
+        from,
+        to,
+        rounding,
+    })
+}
+
+/// Price, price decimals and token decimals of every asset of `owner_addr`, from one
+/// `getAllPriceToConvertToUsd` call with zero amounts. The registry wants one amount per
+/// asset; the count comes from the price cache, and `getAllAssets` is only read first
+/// when the owner is not cached or their assets changed since.
+pub async fn fetch_asset_prices(network: &Network, owner_addr: Address) -> Result<Vec<ConversionLeg>, BackendError> {
+
+    let icp_config = IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000);
+    let provider = ProviderBuilder::new().on_icp(icp_config);
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
+
+    let cached = match get_cached_prices(network, owner_addr) {
+        Some(cached) => match contract
+            .getAllPriceToConvertToUsd(owner_addr, vec![Uint::<248, 4>::ZERO; cached.assets.len()])
+            .call()
+            .await
+            .map_err(BackendError::from_call_error)
+        {
+            Err(BackendError::ContractRevert { reason }) if reason.contains(LENGTH_MISMATCH) => None,
+            result => Some(result?),
+        },
+        None => None,
+    };
+    let result = match cached {
+        Some(result) => result,
+        None => {
+            let assets = contract
+                .getAllAssets(owner_addr)
+                .call()
+                .await
+                .map_err(BackendError::from_call_error)?;
+            contract
+                .getAllPriceToConvertToUsd(owner_addr, vec![Uint::<248, 4>::ZERO; assets._0.len()])
+                .call()
+                .await
+                .map_err(BackendError::from_call_error)?
+        }
+    };
+
+    let mut legs = Vec::new();
+    for i in 0..result._0.addresses.len() {
+        let price = I256::from(result._0.prices[i]);
+        legs.push(ConversionLeg {
+            asset_address: format!("{:?}", result._0.addresses[i]),
+            symbol: result._0.symbols[i].clone(),
+            token_decimals: result._0.tokenDecimals[i],
+            price: format_price(price, result._0.priceDecimals[i]),
+            raw_price: price.to_string(),
+            price_decimals: result._0.priceDecimals[i],
+            last_updated_time: result._0.lastUpdatedTimes[i].to::<u64>(),
+        });
+    }
+    Ok(legs)
+}
+
+/// Pick one asset out of `fetch_asset_prices`
+pub fn find_leg(legs: &[ConversionLeg], owner_addr: Address, asset: Address) -> Result<ConversionLeg, BackendError> {
+    let asset_address = format!("{:?}", asset);
+    legs.iter()
+        .find(|leg| leg.asset_address == asset_address)
+        .cloned()
+        .ok_or_else(|| BackendError::AssetNotFound(format!(
+            "Asset {} is not registered for {:?}",
+            asset_address, owner_addr
+        )))
+}
+
+/// USD value with 18 decimals of `raw_amount` at the leg's price, rounded down the
+/// way the registry's `_safeConvertToUsd` does
+pub fn usd_value_at(raw_amount: Uint<248, 4>, leg: &ConversionLeg) -> Result<Uint<248, 4>, BackendError> {
+    let price = positive_price(leg)?;
+    let scale = leg.token_decimals as u32 + leg.price_decimals as u32;
+    if scale >= 18 {
+        mul_div_rounded(raw_amount, price, pow10(scale - 18)?, RoundingMode::Down)
+    } else {
+        let numerator = price.checked_mul(pow10(18 - scale)?)
+            .ok_or(BackendError::Overflow("Overflow in multiplication".to_string()))?;
+        mul_div_rounded(raw_amount, numerator, Uint::from(1), RoundingMode::Down)
+    }
+}
+
+// The registry reports invalid or stale feeds as a zero price
+pub fn positive_price(leg: &ConversionLeg) -> Result<Uint<248, 4>, BackendError> {
+    let price: i128 = leg.raw_price.parse()
+        .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
+    if price <= 0 {
+        return Err(BackendError::StalePrice { asset: leg.asset_address.clone() });
+    }
+    Ok(Uint::<248, 4>::from(price as u128))
+}
+
+fn pow10(exponent: u32) -> Result<Uint<248, 4>, BackendError> {
+    Uint::<248, 4>::from(10).checked_pow(Uint::from(exponent))
+        .ok_or(BackendError::Overflow("Overflow in power of ten".to_string()))
+}
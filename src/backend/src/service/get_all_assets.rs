@@ -3,10 +3,12 @@ use alloy::{
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
-use ic_cdk::{update};
+use ic_cdk::{query, update};
 
-use crate::utils::helper::{get_rpc_service, validate_eth_address, AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{validate_eth_address, AssetPriceRegistry};
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::require_cached_assets;
 
 // Structs
 #[derive(CandidType, Deserialize, Clone)]
@@ -16,16 +18,18 @@ pub struct AssetInfo {
 }
 
 #[update]
-async fn get_all_assets(owner_address: String) -> Result<Vec<AssetInfo>, String> {
+async fn get_all_assets_live(owner_address: String, network: Option<String>) -> Result<Vec<AssetInfo>, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
 
     
-    let result = contract.getAllAssets(owner_addr).call().await.map_err(|e| format!("Contract call failed: {}", e))?;
+    let result = contract.getAllAssets(owner_addr).call().await.map_err(BackendError::from_call_error)?;
     
     let mut assets = Vec::new();
     for i in 0..result._0.len() {
@@ -36,3 +40,18 @@ async fn get_all_assets(owner_address: String) -> Result<Vec<AssetInfo>, String>
     }
     Ok(assets)
 }
+
+#[query]
+fn get_all_assets(owner_address: String, network: Option<String>) -> Result<Vec<AssetInfo>, BackendError> {
+
+    let cached = require_cached_assets(&owner_address, network, "get_all_assets_with_prices_live")?;
+
+    Ok(cached
+        .assets
+        .into_iter()
+        .map(|asset| AssetInfo {
+            address: asset.address,
+            symbol: asset.symbol,
+        })
+        .collect())
+}
\ No newline at end of file
@@ -1,17 +1,21 @@
 
 use alloy::{
+    primitives::Address,
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 
 use candid::{CandidType, Deserialize};
 
-use crate::utils::helper::{format_token_price, get_rpc_service, validate_eth_address, AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{format_token_price, validate_eth_address, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::{require_cached_assets, store_prices};
 
 
 // Structs
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
 pub struct AssetWithPrice {
     pub address: String,
     pub symbol: String,
@@ -22,23 +26,60 @@ pub struct AssetWithPrice {
 }
 
 #[ic_cdk::update]
-async fn get_all_assets_with_prices(owner_wallet: String) -> Result<Vec<AssetWithPrice>, String> {
+async fn get_all_assets_with_prices_live(owner_wallet: String, network: Option<String>) -> Result<Vec<AssetWithPrice>, BackendError> {
 
     let owner_address = validate_eth_address(&owner_wallet)?;
 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    let assets = fetch_all_assets_with_prices(&network, owner_address).await?;
+    store_prices(&network, owner_address, assets.clone());
+
+    if assets.is_empty() {
+        return Err(BackendError::AssetNotFound(format!(
+            "No assets found for: {}",
+            owner_wallet
+        )));
+    }
+
+    Ok(assets)
+}
+
+#[ic_cdk::query]
+fn get_all_assets_with_prices(owner_wallet: String, network: Option<String>) -> Result<Vec<AssetWithPrice>, BackendError> {
+
+    let cached = require_cached_assets(&owner_wallet, network, "get_all_assets_with_prices_live")?;
+
+    if cached.assets.is_empty() {
+        return Err(BackendError::AssetNotFound(format!(
+            "No assets found for: {}",
+            owner_wallet
+        )));
+    }
+
+    Ok(cached.assets)
+}
+
+/// Read every asset of `owner_address` with its current price from the registry,
+/// cross-checked against the network's consensus providers when it has any
+pub async fn fetch_all_assets_with_prices(network: &Network, owner_address: Address) -> Result<Vec<AssetWithPrice>, BackendError> {
+    consensus_read(network, |network| async move {
+        read_all_assets_with_prices(&network, owner_address).await
+    })
+    .await
+}
+
+async fn read_all_assets_with_prices(network: &Network, owner_address: Address) -> Result<Vec<AssetWithPrice>, BackendError> {
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
     
     let result = contract
         .getAllAssetsWithPrices(owner_address)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
-
-    if result.addresses.is_empty() {
-        return Err(format!("No assets found for: {}", owner_wallet));
-    }
+        .map_err(BackendError::from_call_error)?;
 
     // Transform to Vec<AssetWithPrice>
     let assets: Vec<AssetWithPrice> = result.addresses.iter()
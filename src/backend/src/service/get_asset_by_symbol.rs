@@ -5,8 +5,9 @@ use alloy::{
 use candid::{CandidType, Deserialize};
 use ic_cdk::{update};
 
-use crate::utils::helper::{get_rpc_service, validate_eth_address , AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{validate_eth_address , AssetPriceRegistry};
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
 
 // Struct asset information
 #[derive(CandidType, Deserialize, Clone, Debug)]
@@ -19,15 +20,17 @@ pub struct AssetInfoSymbol {
 }
 
 #[update]
-async fn get_asset_by_symbol(owner_address: String, token_symbol: String) -> Result<AssetInfoSymbol, String> {
+async fn get_asset_by_symbol_live(owner_address: String, token_symbol: String, network: Option<String>) -> Result<AssetInfoSymbol, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
 
+    let network = resolve_network(network)?;
+
     // Set up the provider
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service).set_max_response_size(30_000));
     // Create contract instance
     let contract: AssetPriceRegistry::AssetPriceRegistryInstance<alloy::transports::icp::IcpTransport, alloy::providers::RootProvider<alloy::transports::icp::IcpTransport>> = AssetPriceRegistry::new(
-        ASSET_REGISTRY_CONTRACT, 
+        network.registry_address, 
         provider
     );
     
@@ -35,7 +38,7 @@ async fn get_asset_by_symbol(owner_address: String, token_symbol: String) -> Res
     let result = contract.getAssetBySymbol(owner_addr, token_symbol.clone())
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
     
     // Convert the result to our AssetInfoSymbol struct
     Ok(AssetInfoSymbol {
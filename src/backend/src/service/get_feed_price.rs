@@ -0,0 +1,224 @@
+use alloy::{
+    primitives::Address,
+    providers::ProviderBuilder,
+    transports::icp::IcpConfig,
+};
+use candid::{CandidType, Deserialize};
+use ic_cdk::update;
+
+use crate::utils::helper::{format_price_raw, validate_eth_address, AggregatorV3Interface};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::feed_validation::{validate_feed, FeedValidationReport};
+use crate::utils::price_cache::now_seconds;
+use crate::utils::price_feed::{
+    aggregator_round_id, feed_decimals, feed_metadata, latest_round, phase_id, round_at,
+    try_round_data, validate_round, walk_back_rounds, RoundData,
+    DEFAULT_STALE_PRICE_THRESHOLD_SECONDS,
+};
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct FeedPrice {
+    pub feed_address: String,
+    pub price: String,
+    pub raw_price: String,
+    pub decimals: u8,
+    pub round_id: String,
+    /// Proxy phase and round id within that phase's aggregator, decoded from `round_id`
+    pub phase_id: u16,
+    pub aggregator_round_id: String,
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub answered_in_round: String,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct FeedInfo {
+    pub feed_address: String,
+    pub description: String,
+    pub decimals: u8,
+    pub version: String,
+}
+
+/// Latest price of any Chainlink aggregator, without going through the registry.
+/// Fails with `StalePrice` when the answer is not positive, comes from an older round
+/// or is older than `stale_price_threshold` seconds (1 hour by default).
+#[update]
+async fn get_feed_price_live(
+    feed_address: String,
+    stale_price_threshold: Option<u64>,
+    network: Option<String>,
+) -> Result<FeedPrice, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let network = resolve_network(network)?;
+
+    let (round, decimals) = consensus_read(&network, |network| async move {
+        fetch_feed_price(&network, feed).await
+    })
+    .await?;
+    validate_round(
+        feed,
+        &round,
+        stale_price_threshold.unwrap_or(DEFAULT_STALE_PRICE_THRESHOLD_SECONDS),
+        now_seconds(),
+    )?;
+
+    Ok(to_feed_price(feed, &round, decimals))
+}
+
+// Staleness depends on the time of the check, so it is validated after consensus
+async fn fetch_feed_price(
+    network: &Network,
+    feed: Address,
+) -> Result<(RoundData, u8), BackendError> {
+
+    let round = latest_round(network, feed).await?;
+    let decimals = feed_decimals(network, feed).await?;
+    Ok((round, decimals))
+}
+
+/// One historical round of a Chainlink feed. `round_id` is the proxy round id, in decimal.
+#[update]
+async fn get_feed_round_live(
+    feed_address: String,
+    round_id: String,
+    network: Option<String>,
+) -> Result<FeedPrice, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let round_id = parse_round_id(&round_id)?;
+    let network = resolve_network(network)?;
+
+    let round = try_round_data(&network, feed, round_id)
+        .await?
+        .ok_or_else(|| {
+            BackendError::AssetNotFound(format!("Feed {:?} has no round {}", feed, round_id))
+        })?;
+    let decimals = feed_decimals(&network, feed).await?;
+
+    Ok(to_feed_price(feed, &round, decimals))
+}
+
+/// Up to `count` consecutive rounds, newest first, from `from_round_id` (the latest
+/// round if omitted) back through earlier phases of the proxy.
+#[update]
+async fn get_feed_rounds_live(
+    feed_address: String,
+    from_round_id: Option<String>,
+    count: u32,
+    network: Option<String>,
+) -> Result<Vec<FeedPrice>, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let from_round_id = from_round_id.as_deref().map(parse_round_id).transpose()?;
+    let network = resolve_network(network)?;
+
+    let rounds = walk_back_rounds(&network, feed, from_round_id, count).await?;
+    let decimals = feed_decimals(&network, feed).await?;
+
+    Ok(rounds
+        .iter()
+        .map(|round| to_feed_price(feed, round, decimals))
+        .collect())
+}
+
+/// The round that was current at `timestamp` (unix seconds): the last one updated
+/// at or before it, searched across proxy phases.
+#[update]
+async fn get_feed_price_at_live(
+    feed_address: String,
+    timestamp: u64,
+    network: Option<String>,
+) -> Result<FeedPrice, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let network = resolve_network(network)?;
+
+    let round = round_at(&network, feed, timestamp).await?;
+    let decimals = feed_decimals(&network, feed).await?;
+
+    Ok(to_feed_price(feed, &round, decimals))
+}
+
+#[update]
+async fn get_feed_info_live(feed_address: String, network: Option<String>) -> Result<FeedInfo, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let network = resolve_network(network)?;
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service));
+    let aggregator = AggregatorV3Interface::new(feed, provider);
+
+    let description = aggregator
+        .description()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+    let decimals = aggregator
+        .decimals()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+    let version = aggregator
+        .version()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+
+    Ok(FeedInfo {
+        feed_address: format!("{:?}", feed),
+        description,
+        decimals,
+        version: version.to_string(),
+    })
+}
+
+/// Pre-flight check of a feed before `add_asset` registers it under `symbol`. Problems
+/// are reported, not returned as errors, so the caller can decide to go ahead anyway.
+#[update]
+async fn validate_price_feed_live(
+    feed_address: String,
+    symbol: String,
+    stale_price_threshold: Option<u64>,
+    network: Option<String>,
+) -> Result<FeedValidationReport, BackendError> {
+
+    let feed = validate_eth_address(&feed_address)?;
+    let network = resolve_network(network)?;
+
+    let metadata = feed_metadata(&network, feed).await?;
+    validate_feed(
+        &network,
+        feed,
+        &metadata,
+        &symbol,
+        stale_price_threshold.unwrap_or(DEFAULT_STALE_PRICE_THRESHOLD_SECONDS),
+    )
+    .await
+}
+
+fn to_feed_price(feed: Address, round: &RoundData, decimals: u8) -> FeedPrice {
+    FeedPrice {
+        feed_address: format!("{:?}", feed),
+        price: format_price_raw(round.answer, decimals),
+        raw_price: round.answer.to_string(),
+        decimals,
+        round_id: round.round_id.to_string(),
+        phase_id: phase_id(round.round_id),
+        aggregator_round_id: aggregator_round_id(round.round_id).to_string(),
+        started_at: round.started_at,
+        updated_at: round.updated_at,
+        answered_in_round: round.answered_in_round.to_string(),
+    }
+}
+
+fn parse_round_id(round_id: &str) -> Result<u128, BackendError> {
+    round_id.trim().parse::<u128>().map_err(|_| {
+        BackendError::InvalidInput(format!("Round id must be a decimal integer, got {}", round_id))
+    })
+}
@@ -1,15 +1,18 @@
 use alloy::{
+    primitives::Address,
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
 use ic_cdk::{update};
 
-use crate::utils::helper::{get_rpc_service, validate_eth_address, AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{validate_eth_address, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
 
 
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
 pub struct PriceFeedDetails {
     pub round_id: String, 
     pub answer: i128,
@@ -19,25 +22,35 @@ pub struct PriceFeedDetails {
 }
 
 #[update]
-async fn get_price_feed_details(owner_address: String, asset_address: String) -> Result<PriceFeedDetails, String> {
+async fn get_price_feed_details_live(owner_address: String, asset_address: String, network: Option<String>) -> Result<PriceFeedDetails, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
     let asset_addr = validate_eth_address(&asset_address)?;
 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    consensus_read(&network, |network| async move {
+        fetch_price_feed_details(&network, owner_addr, asset_addr).await
+    })
+    .await
+}
+
+async fn fetch_price_feed_details(network: &Network, owner_addr: Address, asset_addr: Address) -> Result<PriceFeedDetails, BackendError> {
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
 
     let result = contract.getPriceFeedDetails(owner_addr, asset_addr)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
     
     // Convert U256 to u64, handling potential overflow
     let started_at = result.startedAt.try_into()
-        .map_err(|_| "startedAt value too large for u64")?;
+        .map_err(|_| BackendError::Overflow("startedAt value too large for u64".to_string()))?;
     let updated_at = result.updatedAt.try_into()
-        .map_err(|_| "updatedAt value too large for u64")?;
+        .map_err(|_| BackendError::Overflow("updatedAt value too large for u64".to_string()))?;
     
     // Convert Uint<80, 2> (uint80) to String to handle large values
     let round_id = result.roundId.to_string();
@@ -45,7 +58,7 @@ async fn get_price_feed_details(owner_address: String, asset_address: String) ->
     
     // Convert I256 to i128 - using into() with bounds checking
     let answer: i128 = result.answer.try_into()
-        .map_err(|_| "answer value out of range for i128")?;
+        .map_err(|_| BackendError::Overflow("answer value out of range for i128".to_string()))?;
     
     Ok(PriceFeedDetails {
         round_id,
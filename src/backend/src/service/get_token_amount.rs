@@ -1,12 +1,15 @@
 use alloy::{
+    primitives::{Address, Uint},
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
 use ic_cdk::update;
-use crate::ASSET_REGISTRY_CONTRACT;
 
-use crate::utils::helper::{validate_eth_address, get_rpc_service, parse_usd_value, format_with_decimals, AssetPriceRegistry};
+use crate::utils::helper::{validate_eth_address, parse_usd_value, format_with_decimals, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
 
 #[derive(CandidType, Deserialize, Clone)]
 pub struct TokenAmountResult {
@@ -17,31 +20,47 @@ pub struct TokenAmountResult {
 
 
 #[update]
-async fn get_token_amount(
+async fn get_token_amount_live(
     owner_address: String,
     asset_address: String,
     usd_value: String,
     decimals: u8,
-) -> Result<TokenAmountResult, String> {
+    network: Option<String>,
+) -> Result<TokenAmountResult, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
     let asset_addr = validate_eth_address(&asset_address)?;
     
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
-    
     let usd_val = parse_usd_value(&usd_value)?;
-    
-    let result = contract.getTokenAmount(owner_addr, asset_addr, usd_val)
-        .call()
-        .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
-    
-    let amount_value = result._0;
+
+    let amount_value = consensus_read(&network, |network| async move {
+        fetch_token_amount(&network, owner_addr, asset_addr, usd_val).await
+    })
+    .await?;
     
     Ok(TokenAmountResult {
         amount: format_with_decimals(amount_value, decimals), 
         raw_amount: amount_value.to_string(),
     })
+}
+
+async fn fetch_token_amount(
+    network: &Network,
+    owner_addr: Address,
+    asset_addr: Address,
+    usd_val: Uint<248, 4>,
+) -> Result<Uint<248, 4>, BackendError> {
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
+
+    let result = contract.getTokenAmount(owner_addr, asset_addr, usd_val)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+
+    Ok(result._0)
 }
\ No newline at end of file
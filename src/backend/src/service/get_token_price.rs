@@ -1,14 +1,18 @@
 use alloy::{
+    primitives::Address,
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
-use ic_cdk::{update};
+use ic_cdk::{query, update};
 
-use crate::utils::helper::{get_rpc_service, validate_eth_address, format_price_raw, AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{validate_eth_address, format_price_raw, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::require_cached_assets;
 
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
 pub struct TokenPriceResult {
     pub price: String,
     pub decimals: u8,
@@ -16,23 +20,33 @@ pub struct TokenPriceResult {
 }
 
 #[update]
-async fn get_token_price(owner_address: String, asset_address: String) -> Result<TokenPriceResult, String> {
+async fn get_token_price_live(owner_address: String, asset_address: String, network: Option<String>) -> Result<TokenPriceResult, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
     let asset_addr = validate_eth_address(&asset_address)?;
 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    consensus_read(&network, |network| async move {
+        fetch_token_price(&network, owner_addr, asset_addr).await
+    })
+    .await
+}
+
+async fn fetch_token_price(network: &Network, owner_addr: Address, asset_addr: Address) -> Result<TokenPriceResult, BackendError> {
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
     
     let result = contract.getTokenPrice(owner_addr, asset_addr)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
     
     // Convert i256 to i128 with bounds checking
     let raw_price: i128 = result.price.try_into()
-        .map_err(|_| "price value out of range for i128")?;
+        .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
     
     let formatted_price = format_price_raw(raw_price, result.decimals);
     
@@ -41,4 +55,26 @@ async fn get_token_price(owner_address: String, asset_address: String) -> Result
         decimals: result.decimals,
         raw_price,
     })
+}
+
+#[query]
+fn get_token_price(owner_address: String, asset_address: String, network: Option<String>) -> Result<TokenPriceResult, BackendError> {
+
+    let asset_addr = format!("{:?}", validate_eth_address(&asset_address)?);
+    let cached = require_cached_assets(&owner_address, network, "get_all_assets_with_prices_live")?;
+
+    let asset = cached
+        .assets
+        .into_iter()
+        .find(|asset| asset.address == asset_addr)
+        .ok_or_else(|| BackendError::AssetNotFound(format!("No cached price for asset {}", asset_address)))?;
+
+    let raw_price: i128 = asset.raw_price.parse()
+        .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
+
+    Ok(TokenPriceResult {
+        price: format_price_raw(raw_price, asset.decimals),
+        decimals: asset.decimals,
+        raw_price,
+    })
 }
\ No newline at end of file
@@ -1,14 +1,18 @@
 use alloy::{
+    primitives::Address,
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
-use ic_cdk::{update};
-use crate::ASSET_REGISTRY_CONTRACT;
+use ic_cdk::{query, update};
 
-use crate::utils::helper::{get_rpc_service, validate_eth_address, format_price_raw, AssetPriceRegistry};
+use crate::utils::helper::{validate_eth_address, format_price_raw, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::require_cached_assets;
 
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
 pub struct TokenPriceResultSymbol {
     pub price: String,
     pub decimals: u8,
@@ -17,22 +21,33 @@ pub struct TokenPriceResultSymbol {
 
 
 #[update]
-async fn get_token_price_by_symbol(owner_address: String, symbol: String) -> Result<TokenPriceResultSymbol, String> {
+async fn get_token_price_by_symbol_live(owner_address: String, symbol: String, network: Option<String>) -> Result<TokenPriceResultSymbol, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()).set_max_response_size(30_000));
+    let network = resolve_network(network)?;
 
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    consensus_read(&network, |network| {
+        let symbol = symbol.clone();
+        async move { fetch_token_price_by_symbol(&network, owner_addr, symbol).await }
+    })
+    .await
+}
+
+async fn fetch_token_price_by_symbol(network: &Network, owner_addr: Address, symbol: String) -> Result<TokenPriceResultSymbol, BackendError> {
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
     
     let result = contract.getTokenPriceBySymbol(owner_addr, symbol)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
     
     // Convert i256 to i128 with bounds checking
     let raw_price: i128 = result.price.try_into()
-        .map_err(|_| "price value out of range for i128")?;
+        .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
     
     let formatted_price = format_price_raw(raw_price, result.decimals);
     
@@ -41,4 +56,26 @@ async fn get_token_price_by_symbol(owner_address: String, symbol: String) -> Res
         decimals: result.decimals,
         raw_price,
     })
+}
+
+#[query]
+fn get_token_price_by_symbol(owner_address: String, symbol: String, network: Option<String>) -> Result<TokenPriceResultSymbol, BackendError> {
+
+    let cached = require_cached_assets(&owner_address, network, "get_all_assets_with_prices_live")?;
+
+    // Symbols are case-insensitive in the registry
+    let asset = cached
+        .assets
+        .into_iter()
+        .find(|asset| asset.symbol.eq_ignore_ascii_case(&symbol))
+        .ok_or_else(|| BackendError::AssetNotFound(format!("No cached price for symbol {}", symbol)))?;
+
+    let raw_price: i128 = asset.raw_price.parse()
+        .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
+
+    Ok(TokenPriceResultSymbol {
+        price: format_price_raw(raw_price, asset.decimals),
+        decimals: asset.decimals,
+        raw_price,
+    })
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use alloy::primitives::Address;
+use candid::{CandidType, Deserialize};
+use ic_cdk::{query, update};
+
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::{format_price_raw, validate_eth_address};
+use crate::utils::price_cache::now_seconds;
+use crate::utils::price_feed::{feed_decimals, registered_feed};
+use crate::utils::twap::{
+    history_samples, round_samples, time_weighted_average, validate_window, TimeWeightedAverage,
+    DEFAULT_MIN_SAMPLES,
+};
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct TwapResult {
+    pub asset_address: String,
+    pub price: String,
+    pub raw_price: String,
+    pub decimals: u8,
+    /// Window covered, unix seconds
+    pub start: u64,
+    pub end: u64,
+    pub samples: u32,
+    pub covered_seconds: u64,
+    /// Seconds at the start of the window before the first sample
+    pub coverage_gap_seconds: u64,
+}
+
+/// Time weighted average price over the last `window_seconds`, from the recorded
+/// price history. Fails with fewer than `min_samples` prices (2 by default).
+#[query]
+fn get_twap(
+    owner_address: String,
+    asset_address: String,
+    window_seconds: u64,
+    min_samples: Option<u32>,
+    network: Option<String>,
+) -> Result<TwapResult, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let asset_addr = validate_eth_address(&asset_address)?;
+    validate_window(window_seconds)?;
+    let network = resolve_network(network)?;
+
+    let end = now_seconds();
+    let start = end.saturating_sub(window_seconds);
+    let (samples, decimals) = history_samples(&network, owner_addr, asset_addr, start, end)?;
+    let average = time_weighted_average(
+        &samples,
+        start,
+        end,
+        min_samples.unwrap_or(DEFAULT_MIN_SAMPLES),
+    )?;
+
+    Ok(to_twap_result(asset_addr, average, decimals, start, end))
+}
+
+/// Same as `get_twap`, computed from the rounds of the asset's Chainlink feed.
+/// Reads at most 100 rounds, a longer window shows up as a coverage gap.
+#[update]
+async fn get_twap_live(
+    owner_address: String,
+    asset_address: String,
+    window_seconds: u64,
+    min_samples: Option<u32>,
+    network: Option<String>,
+) -> Result<TwapResult, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let asset_addr = validate_eth_address(&asset_address)?;
+    validate_window(window_seconds)?;
+    let network = resolve_network(network)?;
+
+    let feed = registered_feed(&network, owner_addr, asset_addr).await?;
+    let decimals = feed_decimals(&network, feed).await?;
+
+    let end = now_seconds();
+    let start = end.saturating_sub(window_seconds);
+    let samples = round_samples(&network, feed, start).await?;
+    let average = time_weighted_average(
+        &samples,
+        start,
+        end,
+        min_samples.unwrap_or(DEFAULT_MIN_SAMPLES),
+    )?;
+
+    Ok(to_twap_result(asset_addr, average, decimals, start, end))
+}
+
+fn to_twap_result(
+    asset_address: Address,
+    average: TimeWeightedAverage,
+    decimals: u8,
+    start: u64,
+    end: u64,
+) -> TwapResult {
+    TwapResult {
+        asset_address: format!("{:?}", asset_address),
+        price: format_price_raw(average.price, decimals),
+        raw_price: average.price.to_string(),
+        decimals,
+        start,
+        end,
+        samples: average.samples,
+        covered_seconds: average.covered_seconds,
+        coverage_gap_seconds: average.coverage_gap_seconds,
+    }
+}
@@ -1,12 +1,14 @@
 use alloy::{
-    primitives::{U256 },
+    primitives::{Address, Uint, U256},
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
 use candid::{CandidType, Deserialize};
 use ic_cdk::update;
-use crate::utils::helper::{get_rpc_service, validate_eth_address, format_usd_amount, parse_token_amount, AssetPriceRegistry};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{validate_eth_address, format_usd_amount, parse_token_amount, AssetPriceRegistry};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
 
 #[derive(CandidType, Deserialize)]
 pub struct UsdValueResult {
@@ -17,12 +19,13 @@ pub struct UsdValueResult {
 }
 
 #[update]
-async fn get_usd_value(
+async fn get_usd_value_live(
     owner_address: String,
     asset_address: String,
     token_amount: String,
-    decimals: u8, 
-) -> Result<UsdValueResult, String> {
+    decimals: u8,
+    network: Option<String>,
+) -> Result<UsdValueResult, BackendError> {
 
     let owner_addr = validate_eth_address(&owner_address)?;
     let asset_addr = validate_eth_address(&asset_address)?;
@@ -30,26 +33,41 @@ async fn get_usd_value(
     // Parse token amount with custom decimals
     let parsed_amount = parse_token_amount(&token_amount, decimals)?;
 
+    let network = resolve_network(network)?;
+
+    let usd_value_u256 = consensus_read(&network, |network| async move {
+        fetch_usd_value(&network, owner_addr, asset_addr, parsed_amount).await
+    })
+    .await?;
+
+    // Format the result
+    Ok(UsdValueResult {
+        usd_value: format_usd_amount(usd_value_u256),
+        raw_result: usd_value_u256.to_string(),
+        asset_address,
+        token_amount,
+    })
+}
+
+async fn fetch_usd_value(
+    network: &Network,
+    owner_addr: Address,
+    asset_addr: Address,
+    parsed_amount: Uint<248, 4>,
+) -> Result<U256, BackendError> {
+
     // Setup provider and contract
-    let icp_config = IcpConfig::new(get_rpc_service()).set_max_response_size(30_000);
+    let icp_config = IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000);
     let provider = ProviderBuilder::new().on_icp(icp_config);
-    let contract = AssetPriceRegistry::new(ASSET_REGISTRY_CONTRACT, provider);
+    let contract = AssetPriceRegistry::new(network.registry_address, provider);
 
     // Call the contract function
     let usd_value = contract.getUsdValue(owner_addr, asset_addr, parsed_amount)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?
+        .map_err(BackendError::from_call_error)?
         ._0; // Access the inner value of the return tuple
 
     // Convert from Uint<248, 4> to U256
-    let usd_value_u256 = U256::from_limbs(usd_value.into_limbs());
-
-    // Format the result
-    Ok(UsdValueResult {
-        usd_value: format_usd_amount(usd_value_u256),
-        raw_result: usd_value_u256.to_string(),
-        asset_address,
-        token_amount,
-    })
+    Ok(U256::from_limbs(usd_value.into_limbs()))
 }
\ No newline at end of file
@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use alloy::{primitives::Address, signers::Signer};
+use candid::{CandidType, Deserialize, Principal};
+use ic_cdk::update;
+
+use crate::service::convert_tokens::fetch_asset_prices;
+use crate::service::value_portfolio::{
+    unvalued_position, value_holdings, PortfolioValuation, PositionStatus,
+};
+use crate::utils::config::resolve_network;
+use crate::utils::consensus::consensus_read;
+use crate::utils::erc20::{balance_of, token_metadata};
+use crate::utils::error::BackendError;
+use crate::utils::helper::{auth_guard, format_token_amount, validate_eth_address};
+use crate::utils::tx_submitter::create_signer;
+
+// Balances read by one wallet valuation, each one is an HTTPS outcall
+pub const MAX_WALLET_ASSETS: usize = 50;
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct TokenMetadataResult {
+    pub token_address: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct WalletValuation {
+    pub principal: Principal,
+    pub wallet_address: String,
+    /// One position per asset registered under the owner, valued at the owner's prices
+    pub valuation: PortfolioValuation,
+}
+
+/// `symbol()` and `decimals()` of any ERC-20 token
+#[update]
+async fn get_token_metadata_live(token_address: String, network: Option<String>) -> Result<TokenMetadataResult, BackendError> {
+
+    let token = validate_eth_address(&token_address)?;
+    let network = resolve_network(network)?;
+
+    let metadata = token_metadata(&network, token).await?;
+    Ok(TokenMetadataResult {
+        token_address: format!("{:?}", token),
+        symbol: metadata.symbol,
+        decimals: metadata.decimals,
+    })
+}
+
+/// What the canister wallet of `principal` (the caller by default) holds of every
+/// asset registered under `owner_address`, and what it is worth at the registry prices.
+/// An asset whose balance cannot be read is flagged and left out of the total.
+#[update]
+async fn get_wallet_value_live(
+    owner_address: String,
+    principal: Option<Principal>,
+    network: Option<String>,
+) -> Result<WalletValuation, BackendError> {
+
+    auth_guard()?;
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let network = resolve_network(network)?;
+
+    // If no principal is specified in call, attempt to use caller principal
+    let principal = principal.unwrap_or_else(ic_cdk::caller);
+    let wallet = create_signer(&principal).await?.address();
+
+    let legs = consensus_read(&network, |network| async move {
+        fetch_asset_prices(&network, owner_addr).await
+    })
+    .await?;
+    if legs.len() > MAX_WALLET_ASSETS {
+        return Err(BackendError::InvalidInput(format!(
+            "Owner has {} assets, at most {} balances can be read per call",
+            legs.len(),
+            MAX_WALLET_ASSETS
+        )));
+    }
+
+    let mut holdings = Vec::with_capacity(legs.len());
+    // Positions without a balance, with their index among the owner's assets
+    let mut unreadable = Vec::new();
+    for (index, leg) in legs.iter().enumerate() {
+        let asset = Address::from_str(&leg.asset_address)
+            .map_err(|e| BackendError::InvalidAddress(e.to_string()))?;
+        match balance_of(&network, asset, wallet).await {
+            Ok(balance) => {
+                holdings.push((asset, format_token_amount(balance, leg.token_decimals)))
+            }
+            // Out of cycles, the remaining reads would fail too
+            Err(e @ BackendError::InsufficientCycles(_)) => return Err(e),
+            Err(e) => unreadable.push((
+                index,
+                unvalued_position(
+                    leg.asset_address.clone(),
+                    leg.symbol.clone(),
+                    String::new(),
+                    PositionStatus::BalanceUnavailable {
+                        reason: e.to_string(),
+                    },
+                ),
+            )),
+        }
+    }
+
+    let mut valuation = value_holdings(owner_addr, &legs, holdings)?;
+    // Ascending indices, so each lands where it was among the owner's assets
+    for (index, position) in unreadable {
+        valuation.positions.insert(index, position);
+        valuation.unvalued_positions += 1;
+    }
+
+    Ok(WalletValuation {
+        principal,
+        wallet_address: format!("{:?}", wallet),
+        valuation,
+    })
+}
@@ -3,15 +3,25 @@ pub mod add_remove_asset {
     mod get_balance;
     pub mod add_asset;
     pub mod remove_asset;
+    pub mod update_symbol;
+    mod resync_nonce;
 }
+pub mod config;
 pub mod get_all_assets_with_prices;
 pub mod get_all_assets;
 pub mod convert_tokens;
 pub mod safe_get_price;
 pub mod get_asset_by_symbol;
 pub mod get_price_feed_details;
+pub mod get_feed_price;
 pub mod get_token_price;
 pub mod get_token_amount;
 pub mod get_token_price_by_symbol;
 pub mod get_usd_value;
+pub mod get_twap;
+pub mod get_wallet_value;
+pub mod price_cache;
+pub mod price_history;
+pub mod transactions;
+pub mod value_portfolio;
 
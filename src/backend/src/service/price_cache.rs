@@ -0,0 +1,79 @@
+use candid::{CandidType, Deserialize};
+use ic_cdk::{query, update};
+
+use crate::service::get_all_assets_with_prices::AssetWithPrice;
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::{controller_guard, validate_eth_address};
+use crate::utils::price_cache::{
+    get_cached_prices as get_cached_entry, get_refresh_config, try_refresh_prices,
+    set_refresh_config, CachedPrices, PriceRefreshConfig,
+};
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct CachedAssetPrice {
+    pub asset: AssetWithPrice,
+    pub fetched_at: u64,
+}
+
+#[query]
+fn get_cached_prices(owner_address: String, network: Option<String>) -> Result<CachedPrices, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let network = resolve_network(network)?;
+
+    get_cached_entry(&network, owner_addr)
+        .ok_or_else(|| {
+            BackendError::AssetNotFound(format!(
+                "No cached prices for {} on {}",
+                owner_address, network.name
+            ))
+        })
+}
+
+#[query]
+fn get_cached_price(owner_address: String, asset_address: String, network: Option<String>) -> Result<CachedAssetPrice, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let asset_addr = format!("{:?}", validate_eth_address(&asset_address)?);
+    let network = resolve_network(network)?;
+
+    let cached = get_cached_entry(&network, owner_addr)
+        .ok_or_else(|| {
+            BackendError::AssetNotFound(format!(
+                "No cached prices for {} on {}",
+                owner_address, network.name
+            ))
+        })?;
+
+    let fetched_at = cached.fetched_at;
+    cached
+        .assets
+        .into_iter()
+        .find(|asset| asset.address == asset_addr)
+        .map(|asset| CachedAssetPrice { asset, fetched_at })
+        .ok_or_else(|| {
+            BackendError::AssetNotFound(format!("No cached price for asset {}", asset_address))
+        })
+}
+
+#[query]
+fn get_price_refresh_config() -> PriceRefreshConfig {
+    get_refresh_config()
+}
+
+#[update]
+fn set_price_refresh_config(config: PriceRefreshConfig) -> Result<(), BackendError> {
+    controller_guard()?;
+
+    set_refresh_config(config)
+}
+
+/// Refresh the cache immediately instead of waiting for the next timer tick.
+/// Returns an error instead of skipping silently when a refresh is already running.
+#[update]
+async fn refresh_cached_prices() -> Result<(), BackendError> {
+    controller_guard()?;
+
+    try_refresh_prices().await
+}
@@ -0,0 +1,39 @@
+use ic_cdk::query;
+
+use crate::utils::config::resolve_network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::validate_eth_address;
+use crate::utils::price_history::{candles, price_history, Candle, PricePoint};
+
+/// Recorded prices of an asset between `from` and `to` (unix seconds, inclusive).
+/// Prices are recorded by the background refresher and by `get_all_assets_with_prices_live`.
+#[query]
+fn get_price_history(
+    owner_address: String,
+    asset_address: String,
+    from: u64,
+    to: u64,
+    network: Option<String>,
+) -> Result<Vec<PricePoint>, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let asset_addr = validate_eth_address(&asset_address)?;
+    let network = resolve_network(network)?;
+
+    price_history(&network, owner_addr, asset_addr, from, to)
+}
+
+#[query]
+fn get_candles(
+    owner_address: String,
+    asset_address: String,
+    interval_seconds: u64,
+    network: Option<String>,
+) -> Result<Vec<Candle>, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+    let asset_addr = validate_eth_address(&asset_address)?;
+    let network = resolve_network(network)?;
+
+    candles(&network, owner_addr, asset_addr, interval_seconds)
+}
@@ -1,29 +1,51 @@
 use alloy::{
+    primitives::Address,
     providers::ProviderBuilder,
     transports::icp::IcpConfig,
 };
-use ic_cdk::update;
+use ic_cdk::{query, update};
 use candid::{CandidType, Deserialize};
-use crate::utils::helper::{AssetPriceRegistry, validate_eth_address, get_rpc_service};
-use crate::ASSET_REGISTRY_CONTRACT;
+use crate::utils::helper::{AssetPriceRegistry, validate_eth_address};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::price_cache::{max_cache_age, now_seconds, require_cached_assets};
 
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Clone, PartialEq)]
 pub struct PriceInfo {
     pub raw_price: String,
     pub decimals: u8,
     pub formatted_price: String, 
+    /// Unix time in seconds the price was read from the registry
+    pub fetched_at: u64,
 }
 
 #[update]
-async fn safe_get_price(owner_address: String, asset_address: String) -> Result<PriceInfo, String> {
+async fn safe_get_price_live(owner_address: String, asset_address: String, network: Option<String>) -> Result<PriceInfo, BackendError> {
     
     let owner_addr = validate_eth_address(&owner_address)?;
     let asset_addr = validate_eth_address(&asset_address)?;
 
+    let network = resolve_network(network)?;
+
+    let price = consensus_read(&network, |network| async move {
+        fetch_safe_price(&network, owner_addr, asset_addr).await
+    })
+    .await?;
+
+    // Set after consensus, providers answer at slightly different times
+    Ok(PriceInfo {
+        fetched_at: now_seconds(),
+        ..price
+    })
+}
+
+async fn fetch_safe_price(network: &Network, owner_addr: Address, asset_addr: Address) -> Result<PriceInfo, BackendError> {
+
     // Set up provider 
-    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(get_rpc_service()));
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
     let contract = AssetPriceRegistry::new(
-        ASSET_REGISTRY_CONTRACT, 
+        network.registry_address, 
         provider
     );
 
@@ -31,29 +53,61 @@ async fn safe_get_price(owner_address: String, asset_address: String) -> Result<
     let result = contract._safeGetPrice(owner_addr, asset_addr)
         .call()
         .await
-        .map_err(|e| format!("Contract call failed: {}", e))?;
+        .map_err(BackendError::from_call_error)?;
     
     let raw_price = result._0;  
     let decimals = result._1;  
     
-    let formatted_price = {
-        let raw_str = raw_price.to_string();
-        if decimals == 0 {
-            raw_str
-        } else {
-            let len = raw_str.len();
-            if len > decimals as usize {
-                let split_pos = len - decimals as usize;
-                format!("{}.{}", &raw_str[..split_pos], &raw_str[split_pos..])
-            } else {
-                format!("0.{}", "0".repeat(decimals as usize - len) + &raw_str)
-            }
-        }
-    };
-    
     Ok(PriceInfo {
         raw_price: raw_price.to_string(),
         decimals,
-        formatted_price,
+        formatted_price: format_safe_price(&raw_price.to_string(), decimals),
+        fetched_at: 0,
     })
+}
+
+#[query]
+fn safe_get_price(owner_address: String, asset_address: String, network: Option<String>) -> Result<PriceInfo, BackendError> {
+
+    let asset_addr = format!("{:?}", validate_eth_address(&asset_address)?);
+    let cached = require_cached_assets(&owner_address, network.clone(), "get_all_assets_with_prices_live")?;
+
+    // A stopped refresher must not turn old prices into safe ones
+    let network = resolve_network(network)?;
+    let owner_addr = validate_eth_address(&owner_address)?;
+    if now_seconds().saturating_sub(cached.fetched_at) > max_cache_age(&network, owner_addr) {
+        return Err(BackendError::StalePrice { asset: asset_address });
+    }
+
+    let asset = cached
+        .assets
+        .into_iter()
+        .find(|asset| asset.address == asset_addr)
+        .ok_or_else(|| BackendError::AssetNotFound(format!("No cached price for asset {}", asset_address)))?;
+
+    // The registry reports invalid or stale feeds as a zero price
+    if asset.raw_price.starts_with('-') || asset.raw_price == "0" {
+        return Err(BackendError::StalePrice { asset: asset_address });
+    }
+
+    Ok(PriceInfo {
+        formatted_price: format_safe_price(&asset.raw_price, asset.decimals),
+        raw_price: asset.raw_price,
+        decimals: asset.decimals,
+        fetched_at: cached.fetched_at,
+    })
+}
+
+fn format_safe_price(raw_str: &str, decimals: u8) -> String {
+    if decimals == 0 {
+        raw_str.to_string()
+    } else {
+        let len = raw_str.len();
+        if len > decimals as usize {
+            let split_pos = len - decimals as usize;
+            format!("{}.{}", &raw_str[..split_pos], &raw_str[split_pos..])
+        } else {
+            format!("0.{}", "0".repeat(decimals as usize - len) + raw_str)
+        }
+    }
 }
\ No newline at end of file
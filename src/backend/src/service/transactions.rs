@@ -0,0 +1,49 @@
+use ic_cdk::{query, update};
+
+use crate::utils::error::BackendError;
+use crate::utils::helper::auth_guard;
+use crate::utils::tx_submitter::{replace_transaction, Replacement, TransactionSubmission};
+use crate::utils::tx_tracker::{get_tracked_transaction, TrackedTransaction, TransactionStatus};
+
+#[query]
+fn get_transaction_status(transaction_hash: String) -> Result<TransactionStatus, BackendError> {
+    get_transaction(transaction_hash).map(|transaction| transaction.status)
+}
+
+#[query]
+fn get_transaction(transaction_hash: String) -> Result<TrackedTransaction, BackendError> {
+    get_tracked_transaction(&transaction_hash).ok_or_else(|| {
+        BackendError::AssetNotFound(format!("Unknown transaction: {}", transaction_hash))
+    })
+}
+
+/// Re-send a pending transaction of the caller with the same nonce and fees
+/// multiplied by `fee_multiplier` (at least 1.1)
+#[update]
+async fn speed_up_transaction(
+    transaction_hash: String,
+    fee_multiplier: f64,
+) -> Result<TransactionSubmission, BackendError> {
+    auth_guard()?;
+
+    let original = get_transaction(transaction_hash.clone())?;
+    let arguments = format!("{:?}", (&transaction_hash, fee_multiplier));
+    replace_transaction(
+        &original,
+        Replacement::SpeedUp { fee_multiplier },
+        "speed_up_transaction",
+        arguments,
+    )
+    .await
+}
+
+/// Replace a pending transaction of the caller with a zero-value transfer to itself,
+/// so the nonce is used up without calling the registry
+#[update]
+async fn cancel_transaction(transaction_hash: String) -> Result<TransactionSubmission, BackendError> {
+    auth_guard()?;
+
+    let original = get_transaction(transaction_hash.clone())?;
+    let arguments = format!("{:?}", transaction_hash);
+    replace_transaction(&original, Replacement::Cancel, "cancel_transaction", arguments).await
+}
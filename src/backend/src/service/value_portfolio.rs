@@ -0,0 +1,286 @@
+use alloy::primitives::{Address, Uint, U256};
+use candid::{CandidType, Deserialize};
+use ic_cdk::update;
+
+use crate::service::convert_tokens::{fetch_asset_prices, find_leg, usd_value_at, ConversionLeg};
+use crate::utils::config::resolve_network;
+use crate::utils::consensus::consensus_read;
+use crate::utils::error::BackendError;
+use crate::utils::helper::{
+    format_usd_amount, format_with_decimals, mul_div_rounded, parse_token_amount,
+    validate_eth_address, RoundingMode,
+};
+
+pub const MAX_HOLDINGS: usize = 50;
+// Weights are percentages with this many decimals
+const WEIGHT_DECIMALS: u8 = 4;
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct Holding {
+    pub asset_address: String,
+    pub amount: String,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PositionStatus {
+    Valued,
+    /// The registry reported a zero price, the feed is invalid or stale
+    StalePrice,
+    /// The asset is not registered for the owner
+    NotRegistered,
+    /// The amount could not be read, e.g. the wallet balance of an asset that is not
+    /// an ERC-20 token
+    BalanceUnavailable { reason: String },
+}
+
+/// One holding and its value. The USD fields are only set for `Valued` positions.
+#[derive(CandidType, Deserialize, Clone)]
+pub struct PortfolioPosition {
+    pub asset_address: String,
+    pub symbol: String,
+    pub amount: String,
+    pub status: PositionStatus,
+    pub usd_value: Option<String>,
+    pub raw_usd_value: Option<String>,
+    /// Share of the total USD value, in percent
+    pub weight_percent: Option<String>,
+    pub price: Option<String>,
+    pub last_updated_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct PortfolioValuation {
+    pub owner_address: String,
+    pub positions: Vec<PortfolioPosition>,
+    /// Sum of the valued positions, 18 decimals
+    pub total_usd_value: String,
+    pub raw_total_usd_value: String,
+    /// Positions left out of the total
+    pub unvalued_positions: u32,
+}
+
+/// Value a list of holdings at the owner's registry prices, with one registry call for owners
+/// in the price cache.
+/// Positions whose price is zero or stale, or whose asset is not registered, are
+/// flagged and left out of the total instead of counting as 0.
+#[update]
+async fn value_portfolio_live(
+    owner_address: String,
+    holdings: Vec<Holding>,
+    network: Option<String>,
+) -> Result<PortfolioValuation, BackendError> {
+
+    let owner_addr = validate_eth_address(&owner_address)?;
+
+    if holdings.is_empty() || holdings.len() > MAX_HOLDINGS {
+        return Err(BackendError::InvalidInput(format!(
+            "Between 1 and {} holdings can be valued per call",
+            MAX_HOLDINGS
+        )));
+    }
+    let asset_addresses = holdings
+        .iter()
+        .map(|holding| validate_eth_address(&holding.asset_address))
+        .collect::<Result<Vec<_>, BackendError>>()?;
+
+    let network = resolve_network(network)?;
+
+    let legs = consensus_read(&network, |network| async move {
+        fetch_asset_prices(&network, owner_addr).await
+    })
+    .await?;
+
+    let holdings = asset_addresses
+        .into_iter()
+        .zip(holdings.into_iter().map(|holding| holding.amount))
+        .collect();
+    value_holdings(owner_addr, &legs, holdings)
+}
+
+/// Value `(asset, amount)` holdings at the prices of `fetch_asset_prices`
+pub fn value_holdings(
+    owner_addr: Address,
+    legs: &[ConversionLeg],
+    holdings: Vec<(Address, String)>,
+) -> Result<PortfolioValuation, BackendError> {
+    // Values first, weights need the total
+    let mut valued: Vec<(PortfolioPosition, Option<Uint<248, 4>>)> = Vec::new();
+    let mut total = Uint::<248, 4>::ZERO;
+    for (asset_addr, amount) in holdings {
+        let Ok(leg) = find_leg(legs, owner_addr, asset_addr) else {
+            valued.push((
+                unvalued_position(
+                    format!("{:?}", asset_addr),
+                    String::new(),
+                    amount,
+                    PositionStatus::NotRegistered,
+                ),
+                None,
+            ));
+            continue;
+        };
+
+        let raw_amount = parse_token_amount(&amount, leg.token_decimals)?;
+        match usd_value_at(raw_amount, &leg) {
+            Ok(value) => {
+                total = total.checked_add(value).ok_or(BackendError::Overflow(
+                    "Overflow in portfolio total".to_string(),
+                ))?;
+                valued.push((valued_position(&leg, amount, value), Some(value)));
+            }
+            Err(BackendError::StalePrice { .. }) => valued.push((
+                unvalued_position(
+                    leg.asset_address,
+                    leg.symbol,
+                    amount,
+                    PositionStatus::StalePrice,
+                ),
+                None,
+            )),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut positions = Vec::with_capacity(valued.len());
+    let mut unvalued_positions = 0;
+    for (mut position, value) in valued {
+        match value {
+            Some(value) if !total.is_zero() => {
+                let hundred = Uint::<248, 4>::from(10).pow(Uint::from(WEIGHT_DECIMALS + 2));
+                let weight = mul_div_rounded(value, hundred, total, RoundingMode::HalfEven)?;
+                position.weight_percent = Some(format_with_decimals(weight, WEIGHT_DECIMALS));
+            }
+            Some(_) => position.weight_percent = Some("0".to_string()),
+            None => unvalued_positions += 1,
+        }
+        positions.push(position);
+    }
+
+    let total_u256 = U256::from(total);
+    Ok(PortfolioValuation {
+        owner_address: format!("{:?}", owner_addr),
+        positions,
+        total_usd_value: format_usd_amount(total_u256),
+        raw_total_usd_value: total_u256.to_string(),
+        unvalued_positions,
+    })
+}
+
+fn valued_position(leg: &ConversionLeg, amount: String, value: Uint<248, 4>) -> PortfolioPosition {
+    let value = U256::from(value);
+    PortfolioPosition {
+        asset_address: leg.asset_address.clone(),
+        symbol: leg.symbol.clone(),
+        amount,
+        status: PositionStatus::Valued,
+        usd_value: Some(format_usd_amount(value)),
+        raw_usd_value: Some(value.to_string()),
+        weight_percent: None,
+        price: Some(leg.price.clone()),
+        last_updated_time: Some(leg.last_updated_time),
+    }
+}
+
+pub fn unvalued_position(
+    asset_address: String,
+    symbol: String,
+    amount: String,
+    status: PositionStatus,
+) -> PortfolioPosition {
+    PortfolioPosition {
+        asset_address,
+        symbol,
+        amount,
+        status,
+        usd_value: None,
+        raw_usd_value: None,
+        weight_percent: None,
+        price: None,
+        last_updated_time: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: Address = Address::repeat_byte(0xaa);
+    const USDC: Address = Address::repeat_byte(1);
+    const WETH: Address = Address::repeat_byte(2);
+    const STALE: Address = Address::repeat_byte(3);
+    const UNKNOWN: Address = Address::repeat_byte(4);
+
+    fn leg(asset: Address, token_decimals: u8, raw_price: &str) -> ConversionLeg {
+        ConversionLeg {
+            asset_address: format!("{:?}", asset),
+            symbol: "TKN".to_string(),
+            token_decimals,
+            price: raw_price.to_string(),
+            raw_price: raw_price.to_string(),
+            price_decimals: 8,
+            last_updated_time: 1_700_000_000,
+        }
+    }
+
+    fn legs() -> Vec<ConversionLeg> {
+        vec![
+            leg(USDC, 6, "100000000"),
+            leg(WETH, 18, "200000000000"),
+            leg(STALE, 18, "0"),
+        ]
+    }
+
+    fn value(holdings: &[(Address, &str)]) -> PortfolioValuation {
+        let holdings = holdings
+            .iter()
+            .map(|(asset, amount)| (*asset, amount.to_string()))
+            .collect();
+        value_holdings(OWNER, &legs(), holdings).unwrap()
+    }
+
+    #[test]
+    fn weights_split_the_total() {
+        // $100 of USDC and $200 of WETH
+        let valuation = value(&[(USDC, "100"), (WETH, "0.1")]);
+
+        assert_eq!(valuation.raw_total_usd_value, "300000000000000000000");
+        assert_eq!(valuation.unvalued_positions, 0);
+        let weights: Vec<Option<String>> = valuation
+            .positions
+            .iter()
+            .map(|position| position.weight_percent.clone())
+            .collect();
+        assert_eq!(weights, vec![Some("33.3333".to_string()), Some("66.6667".to_string())]);
+    }
+
+    #[test]
+    fn zero_total_has_zero_weights() {
+        let valuation = value(&[(USDC, "0"), (WETH, "0")]);
+
+        assert_eq!(valuation.raw_total_usd_value, "0");
+        for position in &valuation.positions {
+            assert_eq!(position.status, PositionStatus::Valued);
+            assert_eq!(position.weight_percent.as_deref(), Some("0"));
+        }
+    }
+
+    #[test]
+    fn stale_and_unregistered_assets_are_left_out() {
+        let valuation = value(&[(USDC, "100"), (STALE, "1"), (UNKNOWN, "5")]);
+
+        assert_eq!(valuation.raw_total_usd_value, "100000000000000000000");
+        assert_eq!(valuation.unvalued_positions, 2);
+        let statuses: Vec<PositionStatus> = valuation
+            .positions
+            .iter()
+            .map(|position| position.status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![PositionStatus::Valued, PositionStatus::StalePrice, PositionStatus::NotRegistered]
+        );
+        assert_eq!(valuation.positions[0].weight_percent.as_deref(), Some("100"));
+        assert!(valuation.positions[1].usd_value.is_none());
+        assert!(valuation.positions[2].weight_percent.is_none());
+    }
+}
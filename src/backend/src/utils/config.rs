@@ -0,0 +1,328 @@
+use std::{borrow::Cow, cell::RefCell, collections::BTreeMap, str::FromStr};
+
+use alloy::{
+    primitives::{address, Address},
+    transports::icp::{RpcApi, RpcService},
+};
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_stable_structures::{storable::Bound, StableCell, Storable};
+
+use crate::memory::{get_memory, Memory, CONFIG_MEMORY_ID};
+use crate::utils::error::BackendError;
+use crate::utils::helper::validate_and_normalize_address;
+
+// Defaults used until the canister is installed or upgraded with explicit arguments
+pub const DEFAULT_NETWORK: &str = "sepolia";
+pub const DEFAULT_REGISTRY_ADDRESS: Address = address!("e1006413d1ae924056a602D5266e86dd2570Ad68");
+pub const DEFAULT_CHAIN_ID: u64 = 11155111; // Sepolia testnet
+pub const DEFAULT_CONFIRMATIONS: u64 = 3;
+pub const MAX_CONFIRMATIONS: u64 = 64;
+// Providers a consensus read may query, the network's own one included
+pub const MAX_CONSENSUS_PROVIDERS: usize = 5;
+
+pub fn default_rpc_service() -> RpcService {
+    // RpcService::EthSepolia(EthSepoliaService::Alchemy)
+    // RpcService::EthMainnet(EthMainnetService::Alchemy)
+    // RpcService::BaseMainnet(L2MainnetService::Alchemy)
+    // RpcService::OptimismMainnet(L2MainnetService::Alchemy)
+    // RpcService::ArbitrumOne(L2MainnetService::Alchemy)
+    RpcService::Custom(RpcApi {
+        url: "https://ic-alloy-evm-rpc-proxy.kristofer-977.workers.dev/eth-sepolia".to_string(),
+        headers: None,
+    })
+}
+
+/// One EVM network the canister can talk to
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct NetworkConfig {
+    pub rpc_service: RpcService,
+    pub chain_id: u64,
+    pub registry_address: String,
+    /// Blocks on top of a transaction before it counts as final, `DEFAULT_CONFIRMATIONS` if unset
+    pub confirmations: Option<u64>,
+    /// Cross-check price and conversion reads against more providers, off if unset
+    pub consensus: Option<ConsensusConfig>,
+}
+
+/// How many providers must return the same result for a read to be trusted
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsensusStrategy {
+    /// Every provider must succeed with the same result
+    Equality,
+    /// At least `min` providers must return the same result, the others may fail or differ
+    Threshold { min: u8 },
+}
+
+/// Providers a read is sent to besides the network's `rpc_service`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ConsensusConfig {
+    pub rpc_services: Vec<RpcService>,
+    pub strategy: ConsensusStrategy,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct CanisterConfig {
+    pub default_network: String,
+    pub networks: BTreeMap<String, NetworkConfig>,
+}
+
+/// Init and upgrade argument. Fields left as `None` keep their current value.
+///
+/// `rpc_service`, `registry_address` and `chain_id` apply to the default network,
+/// after `networks` and `default_network` have been applied.
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct InitArgs {
+    pub networks: Option<Vec<(String, NetworkConfig)>>,
+    pub default_network: Option<String>,
+    pub rpc_service: Option<RpcService>,
+    pub registry_address: Option<String>,
+    pub chain_id: Option<u64>,
+}
+
+/// A network resolved from the configuration, ready to build providers and contracts with
+#[derive(Clone, Debug)]
+pub struct Network {
+    pub name: String,
+    pub rpc_service: RpcService,
+    pub chain_id: u64,
+    pub registry_address: Address,
+    pub confirmations: u64,
+    pub consensus: Option<ConsensusConfig>,
+}
+
+impl Default for CanisterConfig {
+    fn default() -> Self {
+        let mut networks = BTreeMap::new();
+        networks.insert(
+            DEFAULT_NETWORK.to_string(),
+            NetworkConfig {
+                rpc_service: default_rpc_service(),
+                chain_id: DEFAULT_CHAIN_ID,
+                registry_address: format!("{:?}", DEFAULT_REGISTRY_ADDRESS),
+                confirmations: None,
+                consensus: None,
+            },
+        );
+        Self {
+            default_network: DEFAULT_NETWORK.to_string(),
+            networks,
+        }
+    }
+}
+
+/// Single-network configuration stored before networks were named
+#[derive(CandidType, Deserialize)]
+struct LegacyCanisterConfig {
+    rpc_service: RpcService,
+    registry_address: String,
+    chain_id: u64,
+}
+
+impl From<LegacyCanisterConfig> for CanisterConfig {
+    fn from(legacy: LegacyCanisterConfig) -> Self {
+        let mut config = Self::default();
+        if let Some(network) = config.networks.get_mut(DEFAULT_NETWORK) {
+            network.rpc_service = legacy.rpc_service;
+            network.registry_address = legacy.registry_address;
+            network.chain_id = legacy.chain_id;
+        }
+        config
+    }
+}
+
+impl Storable for CanisterConfig {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("failed to encode canister config"))
+    }
+
+    // A legacy configuration becomes the default network. It is written back in the
+    // new layout by the `update_config` every init and upgrade runs.
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self)
+            .or_else(|_| Decode!(bytes.as_ref(), LegacyCanisterConfig).map(Self::from))
+            .expect("failed to decode canister config")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+thread_local! {
+    static CONFIG: RefCell<StableCell<CanisterConfig, Memory>> = RefCell::new(
+        StableCell::init(get_memory(CONFIG_MEMORY_ID), CanisterConfig::default())
+            .expect("failed to initialize canister config"),
+    );
+}
+
+pub fn get_config() -> CanisterConfig {
+    CONFIG.with_borrow(|config| config.get().clone())
+}
+
+/// Apply `f` to a copy of the configuration and store it only if `f` succeeds.
+pub fn update_config<T>(
+    f: impl FnOnce(&mut CanisterConfig) -> Result<T, BackendError>,
+) -> Result<T, BackendError> {
+    CONFIG.with_borrow_mut(|cell| {
+        let mut config = cell.get().clone();
+        let result = f(&mut config)?;
+        cell.set(config).expect("failed to store canister config");
+        Ok(result)
+    })
+}
+
+/// Validate a network entry and normalize its registry address
+pub fn validate_network(network: NetworkConfig) -> Result<NetworkConfig, BackendError> {
+    if network.chain_id == 0 {
+        return Err(BackendError::InvalidInput(
+            "Chain id must be greater than 0".to_string(),
+        ));
+    }
+    if let Some(confirmations) = network.confirmations {
+        validate_confirmations(confirmations)?;
+    }
+    if let Some(consensus) = &network.consensus {
+        validate_consensus(consensus)?;
+    }
+    let registry_address = validate_and_normalize_address(&network.registry_address)?;
+    Ok(NetworkConfig {
+        registry_address: format!("{:?}", registry_address),
+        ..network
+    })
+}
+
+pub fn validate_confirmations(confirmations: u64) -> Result<(), BackendError> {
+    if confirmations == 0 || confirmations > MAX_CONFIRMATIONS {
+        return Err(BackendError::InvalidInput(format!(
+            "Confirmations must be between 1 and {}",
+            MAX_CONFIRMATIONS
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_consensus(consensus: &ConsensusConfig) -> Result<(), BackendError> {
+    let providers = consensus.rpc_services.len() + 1;
+    if providers > MAX_CONSENSUS_PROVIDERS {
+        return Err(BackendError::InvalidInput(format!(
+            "Consensus can use at most {} providers, including the network's own",
+            MAX_CONSENSUS_PROVIDERS
+        )));
+    }
+    if consensus.rpc_services.is_empty() {
+        return Err(BackendError::InvalidInput(
+            "Consensus needs at least one additional RPC service".to_string(),
+        ));
+    }
+    if let ConsensusStrategy::Threshold { min } = consensus.strategy {
+        if min == 0 || min as usize > providers {
+            return Err(BackendError::InvalidInput(format!(
+                "Consensus threshold must be between 1 and {}",
+                providers
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn unknown_network(name: &str) -> BackendError {
+    BackendError::InvalidInput(format!("Unknown network: {}", name))
+}
+
+/// Look up a network by name, or by chain id given as a decimal string.
+/// `None` selects the default network.
+pub fn resolve_network(selector: Option<String>) -> Result<Network, BackendError> {
+    let config = get_config();
+    let selector = selector
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or(config.default_network.clone());
+
+    let (name, network) = config
+        .networks
+        .get_key_value(&selector)
+        .or_else(|| {
+            let chain_id = selector.parse::<u64>().ok()?;
+            config
+                .networks
+                .iter()
+                .find(|(_, network)| network.chain_id == chain_id)
+        })
+        .ok_or_else(|| unknown_network(&selector))?;
+
+    Ok(Network {
+        name: name.clone(),
+        rpc_service: network.rpc_service.clone(),
+        chain_id: network.chain_id,
+        registry_address: Address::from_str(&network.registry_address).map_err(|e| {
+            BackendError::InvalidAddress(format!("Registry address for {}: {}", name, e))
+        })?,
+        confirmations: network.confirmations.unwrap_or(DEFAULT_CONFIRMATIONS),
+        consensus: network.consensus.clone(),
+    })
+}
+
+/// Apply init or upgrade arguments on top of the stored configuration.
+pub fn apply_init_args(args: InitArgs) -> Result<(), BackendError> {
+    update_config(|config| {
+        for (name, network) in args.networks.unwrap_or_default() {
+            config
+                .networks
+                .insert(name.trim().to_lowercase(), validate_network(network)?);
+        }
+        if let Some(default_network) = args.default_network {
+            let default_network = default_network.trim().to_lowercase();
+            if !config.networks.contains_key(&default_network) {
+                return Err(unknown_network(&default_network));
+            }
+            config.default_network = default_network;
+        }
+
+        let default_network = config
+            .networks
+            .get_mut(&config.default_network)
+            .ok_or_else(|| unknown_network(&config.default_network))?;
+        let mut updated = default_network.clone();
+        if let Some(rpc_service) = args.rpc_service {
+            updated.rpc_service = rpc_service;
+        }
+        if let Some(registry_address) = args.registry_address {
+            updated.registry_address = registry_address;
+        }
+        if let Some(chain_id) = args.chain_id {
+            updated.chain_id = chain_id;
+        }
+        *default_network = validate_network(updated)?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_config_becomes_the_default_network() {
+        let legacy = LegacyCanisterConfig {
+            rpc_service: default_rpc_service(),
+            registry_address: "0x0000000000000000000000000000000000000001".to_string(),
+            chain_id: 8453,
+        };
+        let bytes = Encode!(&legacy).unwrap();
+
+        let config = CanisterConfig::from_bytes(Cow::Owned(bytes));
+
+        assert_eq!(config.default_network, DEFAULT_NETWORK);
+        let network = &config.networks[DEFAULT_NETWORK];
+        assert_eq!(network.chain_id, 8453);
+        assert_eq!(network.registry_address, legacy.registry_address);
+    }
+
+    #[test]
+    fn current_config_round_trips() {
+        let config = CanisterConfig::default();
+
+        let decoded = CanisterConfig::from_bytes(config.to_bytes());
+
+        assert_eq!(decoded.default_network, config.default_network);
+        assert_eq!(decoded.networks.len(), config.networks.len());
+    }
+}
@@ -0,0 +1,57 @@
+use std::future::Future;
+
+use crate::utils::config::{ConsensusStrategy, Network};
+use crate::utils::error::BackendError;
+
+/// Run `read` against the network's RPC service and, when the network has consensus
+/// configured, against each of its extra providers in turn.
+///
+/// Results are grouped by equality, errors included, so providers agreeing on a revert
+/// return that revert. The largest group wins if it reaches the strategy's threshold,
+/// otherwise the read fails with `InconsistentResults`.
+pub async fn consensus_read<T, F, Fut>(network: &Network, read: F) -> Result<T, BackendError>
+where
+    T: PartialEq,
+    F: Fn(Network) -> Fut,
+    Fut: Future<Output = Result<T, BackendError>>,
+{
+    let Some(consensus) = network.consensus.clone() else {
+        return read(network.clone()).await;
+    };
+
+    let rpc_services =
+        std::iter::once(network.rpc_service.clone()).chain(consensus.rpc_services);
+    // Distinct results with how many providers returned each
+    let mut results: Vec<(Result<T, BackendError>, u8)> = Vec::new();
+    let mut providers: u8 = 0;
+    for rpc_service in rpc_services {
+        providers += 1;
+        let result = read(Network {
+            rpc_service,
+            consensus: None,
+            ..network.clone()
+        })
+        .await;
+        match results.iter_mut().find(|(other, _)| *other == result) {
+            Some((_, count)) => *count += 1,
+            None => results.push((result, 1)),
+        }
+    }
+
+    let required = match consensus.strategy {
+        ConsensusStrategy::Equality => providers,
+        ConsensusStrategy::Threshold { min } => min,
+    };
+    let (result, agreeing) = results
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .expect("at least one provider is queried");
+    if agreeing < required {
+        return Err(BackendError::InconsistentResults {
+            providers,
+            agreeing,
+            required,
+        });
+    }
+    result
+}
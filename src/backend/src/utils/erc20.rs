@@ -0,0 +1,52 @@
+use alloy::{
+    primitives::{Address, U256},
+    providers::ProviderBuilder,
+    transports::icp::IcpConfig,
+};
+
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::IERC20Metadata;
+
+/// `symbol()` and `decimals()` of an ERC-20 token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+pub async fn token_metadata(
+    network: &Network,
+    token: Address,
+) -> Result<TokenMetadata, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let contract = IERC20Metadata::new(token, provider);
+
+    let symbol = contract
+        .symbol()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+    let decimals = contract
+        .decimals()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+    Ok(TokenMetadata { symbol, decimals })
+}
+
+pub async fn balance_of(
+    network: &Network,
+    token: Address,
+    account: Address,
+) -> Result<U256, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let result = IERC20Metadata::new(token, provider)
+        .balanceOf(account)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    Ok(result._0)
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+
+use candid::{CandidType, Deserialize};
+
+use crate::utils::revert::{decode_revert_reason, revert_reason_from_message, RevertData};
+
+/// Error returned by every endpoint, so clients can branch on the failure kind
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum BackendError {
+    /// Malformed EVM address
+    InvalidAddress(String),
+    /// Address is well formed but its EIP-55 checksum is wrong
+    ChecksumMismatch { provided: String, expected: String },
+    /// Any other argument that failed validation
+    InvalidInput(String),
+    /// Anonymous caller, or a non-controller calling an admin method
+    Unauthorized(String),
+    /// Threshold ECDSA signer could not be set up
+    SignerError(String),
+    /// Transport or JSON-RPC failure while talking to the EVM RPC canister
+    RpcError(String),
+    /// The contract call reverted. `reason` is the decoded `require` message or custom error
+    /// when the RPC response carries revert data
+    ContractRevert { reason: String },
+    /// The price feed did not report a fresh, positive price
+    StalePrice { asset: String },
+    /// Asset, symbol, transaction or cached entry does not exist
+    AssetNotFound(String),
+    /// Arithmetic or conversion overflow
+    Overflow(String),
+    /// Not enough cycles attached or available for the outcall
+    InsufficientCycles(String),
+    /// Fewer than `required` of the network's consensus providers returned the same result
+    InconsistentResults {
+        providers: u8,
+        agreeing: u8,
+        required: u8,
+    },
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAddress(message) => write!(f, "Invalid address: {}", message),
+            Self::ChecksumMismatch { provided, expected } => {
+                write!(f, "Invalid checksum for {}. Use: {}", provided, expected)
+            }
+            Self::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+            Self::Unauthorized(message) => write!(f, "Unauthorized: {}", message),
+            Self::SignerError(message) => write!(f, "Failed to create signer: {}", message),
+            Self::RpcError(message) => write!(f, "RPC error: {}", message),
+            Self::ContractRevert { reason } => write!(f, "Contract reverted: {}", reason),
+            Self::StalePrice { asset } => write!(f, "Price for {} is invalid or stale", asset),
+            Self::AssetNotFound(message) => write!(f, "Not found: {}", message),
+            Self::Overflow(message) => write!(f, "Overflow: {}", message),
+            Self::InsufficientCycles(message) => write!(f, "Insufficient cycles: {}", message),
+            Self::InconsistentResults {
+                providers,
+                agreeing,
+                required,
+            } => write!(
+                f,
+                "Providers disagree: {} of {} returned the same result, {} required",
+                agreeing, providers, required
+            ),
+        }
+    }
+}
+
+impl BackendError {
+    /// Classify an error returned by a `sol!` contract call or provider request.
+    /// Only execution-reverted error responses become `ContractRevert`; they carry the
+    /// decoded Solidity reason when the RPC response includes it.
+    pub fn from_call_error(error: impl fmt::Display + RevertData) -> Self {
+        if let Some(reason) = error.revert_data().and_then(|data| decode_revert_reason(&data)) {
+            return Self::ContractRevert { reason };
+        }
+
+        let message = error.to_string();
+        if error.is_revert() {
+            let reason = revert_reason_from_message(&message).unwrap_or(message);
+            Self::ContractRevert { reason }
+        } else if message.contains("TooFewCycles") || message.contains("OutOfCycles") {
+            Self::InsufficientCycles(message)
+        } else {
+            Self::RpcError(message)
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use alloy::{
+    eips::BlockNumberOrTag,
+    providers::{Provider, ProviderBuilder},
+    transports::icp::IcpConfig,
+};
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+
+// Blocks of history the estimate is based on
+pub const FEE_HISTORY_BLOCKS: u64 = 10;
+// Priority fee percentiles for slow, normal and fast
+const REWARD_PERCENTILES: [f64; 3] = [10.0, 50.0, 90.0];
+
+/// How quickly a transaction should be included
+#[derive(CandidType, Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum FeeSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+/// Fee choice of a write endpoint. `max_fee_per_gas_cap` is a ceiling in wei.
+#[derive(CandidType, Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct FeeOptions {
+    pub speed: Option<FeeSpeed>,
+    pub max_fee_per_gas_cap: Option<String>,
+}
+
+/// EIP-1559 fees in wei
+#[derive(Clone, Copy, Debug)]
+pub struct FeeEstimate {
+    pub base_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl FeeSpeed {
+    fn percentile_index(self) -> usize {
+        match self {
+            Self::Slow => 0,
+            Self::Normal => 1,
+            Self::Fast => 2,
+        }
+    }
+
+    // Blocks of base fee growth to cover, each block can raise it by 12.5%
+    fn headroom_blocks(self) -> u32 {
+        match self {
+            Self::Slow => 1,
+            Self::Normal => 3,
+            Self::Fast => 6,
+        }
+    }
+}
+
+/// Estimate fees for `options` from `eth_feeHistory`.
+///
+/// The priority fee is the median, over recent blocks, of the speed's reward percentile.
+/// The max fee covers the next base fee growing for a few blocks, one more when the
+/// base fee has been rising, and is clamped to the caller's cap.
+pub async fn estimate_fees(
+    network: &Network,
+    options: &FeeOptions,
+) -> Result<FeeEstimate, BackendError> {
+    let speed = options.speed.unwrap_or_default();
+    let cap = options
+        .max_fee_per_gas_cap
+        .as_deref()
+        .map(parse_wei)
+        .transpose()?;
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let history = provider
+        .get_fee_history(FEE_HISTORY_BLOCKS, BlockNumberOrTag::Latest, &REWARD_PERCENTILES)
+        .await
+        .map_err(BackendError::from_call_error)?;
+
+    let base_fee_per_gas = history
+        .next_block_base_fee()
+        .or_else(|| history.base_fee_per_gas.last().copied())
+        .ok_or_else(|| BackendError::RpcError("Fee history has no base fee".to_string()))?;
+
+    let mut rewards: Vec<u128> = history
+        .reward
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|block| block.get(speed.percentile_index()).copied())
+        .collect();
+    let max_priority_fee_per_gas = if rewards.is_empty() {
+        provider
+            .get_max_priority_fee_per_gas()
+            .await
+            .map_err(BackendError::from_call_error)?
+    } else {
+        rewards.sort_unstable();
+        rewards[rewards.len() / 2]
+    };
+
+    let mut headroom_blocks = speed.headroom_blocks();
+    if is_base_fee_rising(&history.base_fee_per_gas) {
+        headroom_blocks += 1;
+    }
+    let max_base_fee = (0..headroom_blocks).fold(base_fee_per_gas, |fee, _| {
+        fee.saturating_mul(9).div_ceil(8)
+    });
+
+    let estimate = FeeEstimate {
+        base_fee_per_gas,
+        max_fee_per_gas: max_base_fee.saturating_add(max_priority_fee_per_gas),
+        max_priority_fee_per_gas,
+    };
+    match cap {
+        Some(cap) => apply_cap(estimate, cap),
+        None => Ok(estimate),
+    }
+}
+
+fn apply_cap(estimate: FeeEstimate, cap: u128) -> Result<FeeEstimate, BackendError> {
+    if cap < estimate.base_fee_per_gas {
+        return Err(BackendError::InvalidInput(format!(
+            "Max fee cap {} is below the current base fee {}",
+            cap, estimate.base_fee_per_gas
+        )));
+    }
+    Ok(FeeEstimate {
+        max_fee_per_gas: estimate.max_fee_per_gas.min(cap),
+        max_priority_fee_per_gas: estimate
+            .max_priority_fee_per_gas
+            .min(cap - estimate.base_fee_per_gas),
+        ..estimate
+    })
+}
+
+// Rising when the newest base fee is above the average of the window
+fn is_base_fee_rising(base_fees: &[u128]) -> bool {
+    let Some(newest) = base_fees.last() else {
+        return false;
+    };
+    let average = base_fees.iter().sum::<u128>() / base_fees.len() as u128;
+    *newest > average
+}
+
+fn parse_wei(value: &str) -> Result<u128, BackendError> {
+    value.trim().parse::<u128>().map_err(|_| {
+        BackendError::InvalidInput(format!("Fee must be an amount in wei, got {}", value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate() -> FeeEstimate {
+        FeeEstimate {
+            base_fee_per_gas: 100,
+            max_fee_per_gas: 150,
+            max_priority_fee_per_gas: 20,
+        }
+    }
+
+    #[test]
+    fn cap_below_base_fee_is_rejected() {
+        assert!(matches!(apply_cap(estimate(), 99), Err(BackendError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn cap_above_estimate_changes_nothing() {
+        let capped = apply_cap(estimate(), 1_000).unwrap();
+        assert_eq!(capped.max_fee_per_gas, 150);
+        assert_eq!(capped.max_priority_fee_per_gas, 20);
+        assert_eq!(capped.base_fee_per_gas, 100);
+    }
+
+    #[test]
+    fn cap_clamps_max_fee_and_priority_fee() {
+        let capped = apply_cap(estimate(), 110).unwrap();
+        assert_eq!(capped.max_fee_per_gas, 110);
+        assert_eq!(capped.max_priority_fee_per_gas, 10);
+
+        let capped = apply_cap(estimate(), 100).unwrap();
+        assert_eq!(capped.max_fee_per_gas, 100);
+        assert_eq!(capped.max_priority_fee_per_gas, 0);
+    }
+
+    #[test]
+    fn rising_base_fee() {
+        assert!(is_base_fee_rising(&[100, 100, 100, 130]));
+        assert!(!is_base_fee_rising(&[130, 120, 110, 100]));
+    }
+
+    #[test]
+    fn flat_base_fee_is_not_rising() {
+        assert!(!is_base_fee_rising(&[100, 100, 100, 100]));
+    }
+
+    #[test]
+    fn short_history_is_not_rising() {
+        assert!(!is_base_fee_rising(&[]));
+        assert!(!is_base_fee_rising(&[100]));
+        assert!(is_base_fee_rising(&[100, 101]));
+    }
+}
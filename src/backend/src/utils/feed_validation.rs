@@ -0,0 +1,186 @@
+use alloy::primitives::Address;
+use candid::{CandidType, Deserialize};
+
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::format_price_raw;
+use crate::utils::price_cache::now_seconds;
+use crate::utils::price_feed::{latest_round, reverted_as_none, FeedMetadata};
+
+/// Pre-flight check of a price feed for `add_asset`. `errors` are problems the registry
+/// would hit when reading the price, `warnings` are signs the feed does not price the
+/// asset in USD. The feed passes when there are no errors.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct FeedValidationReport {
+    pub feed_address: String,
+    pub symbol: String,
+    /// The feed's `description()`, for example `"ETH / USD"`
+    pub description: Option<String>,
+    pub base: Option<String>,
+    pub quote: Option<String>,
+    pub decimals: Option<u8>,
+    pub price: Option<String>,
+    pub raw_price: Option<String>,
+    pub updated_at: Option<u64>,
+    pub age_seconds: Option<u64>,
+    pub stale_price_threshold: u64,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub passed: bool,
+}
+
+/// Check `metadata` read by `feed_metadata` and the `latestRoundData()` of `feed` against
+/// the symbol and staleness threshold an asset would be registered with. Reverts end up
+/// in the report; transport failures are returned as errors.
+pub async fn validate_feed(
+    network: &Network,
+    feed: Address,
+    metadata: &FeedMetadata,
+    symbol: &str,
+    stale_price_threshold: u64,
+) -> Result<FeedValidationReport, BackendError> {
+    let mut report = FeedValidationReport {
+        feed_address: format!("{:?}", feed),
+        symbol: symbol.to_string(),
+        description: None,
+        base: None,
+        quote: None,
+        decimals: None,
+        price: None,
+        raw_price: None,
+        updated_at: None,
+        age_seconds: None,
+        stale_price_threshold,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        passed: false,
+    };
+
+    match metadata.decimals {
+        Some(decimals) => report.decimals = Some(decimals),
+        None => report
+            .errors
+            .push("decimals() reverted, not a Chainlink aggregator".to_string()),
+    }
+
+    match metadata.description.clone() {
+        Some(description) => {
+            match split_pair(&description) {
+                Some((base, quote)) => {
+                    if !quote.eq_ignore_ascii_case("USD") {
+                        report.warnings.push(format!(
+                            "Feed quotes in {}, the registry assumes USD prices",
+                            quote
+                        ));
+                    }
+                    if !matches_symbol(&base, symbol) {
+                        report.warnings.push(format!(
+                            "Feed prices {}, which does not match symbol {}",
+                            base, symbol
+                        ));
+                    }
+                    report.base = Some(base);
+                    report.quote = Some(quote);
+                }
+                None => report.warnings.push(format!(
+                    "Description \"{}\" is not a \"BASE / QUOTE\" pair",
+                    description
+                )),
+            }
+            report.description = Some(description);
+        }
+        None => report.errors.push("description() reverted".to_string()),
+    }
+
+    match reverted_as_none(latest_round(network, feed).await)? {
+        Some(round) => {
+            let now = now_seconds();
+            let age_seconds = now.saturating_sub(round.updated_at);
+            if round.answer <= 0 {
+                report
+                    .errors
+                    .push(format!("Latest answer {} is not positive", round.answer));
+            }
+            if round.answered_in_round < round.round_id {
+                report.errors.push(format!(
+                    "Latest answer comes from round {}, not round {}",
+                    round.answered_in_round, round.round_id
+                ));
+            }
+            if age_seconds > stale_price_threshold {
+                report.errors.push(format!(
+                    "Latest update is {} seconds old, above the threshold of {} seconds",
+                    age_seconds, stale_price_threshold
+                ));
+            }
+            let decimals = report.decimals.unwrap_or(0);
+            report.price = Some(format_price_raw(round.answer, decimals));
+            report.raw_price = Some(round.answer.to_string());
+            report.updated_at = Some(round.updated_at);
+            report.age_seconds = Some(age_seconds);
+        }
+        None => report.errors.push("latestRoundData() reverted".to_string()),
+    }
+
+    report.passed = report.errors.is_empty();
+    Ok(report)
+}
+
+/// `"ETH / USD"` -> `("ETH", "USD")`
+fn split_pair(description: &str) -> Option<(String, String)> {
+    let (base, quote) = description.split_once('/')?;
+    let (base, quote) = (base.trim(), quote.trim());
+    if base.is_empty() || quote.is_empty() {
+        return None;
+    }
+    Some((base.to_string(), quote.to_string()))
+}
+
+/// Wrapped tokens are priced by the feed of their underlying asset, so WETH matches ETH
+fn matches_symbol(base: &str, symbol: &str) -> bool {
+    let symbol = symbol.trim();
+    base.eq_ignore_ascii_case(symbol)
+        || symbol
+            .strip_prefix(['w', 'W'])
+            .is_some_and(|unwrapped| base.eq_ignore_ascii_case(unwrapped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(base: &str, quote: &str) -> Option<(String, String)> {
+        Some((base.to_string(), quote.to_string()))
+    }
+
+    #[test]
+    fn split_pair_trims_around_the_separator() {
+        assert_eq!(split_pair("ETH / USD"), pair("ETH", "USD"));
+        assert_eq!(split_pair("WBTC/USD"), pair("WBTC", "USD"));
+    }
+
+    #[test]
+    fn split_pair_needs_both_sides() {
+        assert_eq!(split_pair("ETH USD"), None);
+        assert_eq!(split_pair("Total Value Locked"), None);
+        assert_eq!(split_pair("ETH / "), None);
+        assert_eq!(split_pair(" / USD"), None);
+    }
+
+    #[test]
+    fn matches_symbol_ignores_case() {
+        assert!(matches_symbol("ETH", "eth"));
+        assert!(matches_symbol("eth", " ETH "));
+        assert!(!matches_symbol("ETH", "BTC"));
+    }
+
+    #[test]
+    fn matches_symbol_accepts_wrapped_tokens() {
+        assert!(matches_symbol("ETH", "WETH"));
+        assert!(matches_symbol("ETH", "weth"));
+        assert!(matches_symbol("WBTC", "WBTC"));
+        assert!(matches_symbol("BTC", "WBTC"));
+        assert!(!matches_symbol("WBTC", "BTC"));
+        assert!(!matches_symbol("ETH", "STETH"));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{cell::RefCell, thread::LocalKey};
+
+/// Holds a `thread_local!` busy flag for as long as it lives.
+///
+/// The flag is cleared on drop, which also happens when a call traps after an await:
+/// the CDK drops the pending future in its cleanup, so the flag cannot stay set.
+pub struct FlagGuard(&'static LocalKey<RefCell<bool>>);
+
+impl FlagGuard {
+    /// Set `flag`, or `None` when it is already set
+    pub fn acquire(flag: &'static LocalKey<RefCell<bool>>) -> Option<Self> {
+        let acquired = flag.with_borrow_mut(|busy| !std::mem::replace(busy, true));
+        acquired.then_some(Self(flag))
+    }
+}
+
+impl Drop for FlagGuard {
+    fn drop(&mut self) {
+        self.0.with_borrow_mut(|busy| *busy = false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    thread_local! {
+        static BUSY: RefCell<bool> = const { RefCell::new(false) };
+    }
+
+    #[test]
+    fn flag_is_held_until_the_guard_drops() {
+        let guard = FlagGuard::acquire(&BUSY).expect("flag is free");
+        assert!(FlagGuard::acquire(&BUSY).is_none());
+
+        drop(guard);
+        assert!(!BUSY.with_borrow(|busy| *busy));
+        assert!(FlagGuard::acquire(&BUSY).is_some());
+    }
+}
@@ -2,11 +2,12 @@ use std::str::FromStr;
 use alloy::{
     primitives::{I256, U256, Uint, Address, utils::format_units},
     sol,
-    transports::icp::{RpcApi, RpcService},
 };
-use candid::Principal;
+use candid::{CandidType, Deserialize, Principal};
 use serde_bytes::ByteBuf;
 
+use crate::utils::error::BackendError;
+
 sol! {
     #[sol(rpc)]
     interface AssetPriceRegistry {
@@ -97,32 +98,85 @@ sol! {
         function removeAsset(
             address assetAddress
         ) external;
+
+        function updateSymbol(
+            address assetAddress,
+            string memory newSymbol
+        ) external;
+
+        event SymbolUpdated(
+            address indexed owner,
+            address indexed asset,
+            string oldSymbol,
+            string newSymbol
+        );
+
+        error InvalidPriceFeed();
+        error InvalidPriceFeedData();
+        error StalePriceFeedData();
+        error AssetNotSupported();
     }
-}
 
-// Modify this function to determine which EVM network canister connects to
-pub fn get_rpc_service() -> RpcService {
-    // RpcService::EthSepolia(EthSepoliaService::Alchemy)
-    // RpcService::EthMainnet(EthMainnetService::Alchemy)
-    // RpcService::BaseMainnet(L2MainnetService::Alchemy)
-    // RpcService::OptimismMainnet(L2MainnetService::Alchemy)
-    // RpcService::ArbitrumOne(L2MainnetService::Alchemy)
-    RpcService::Custom(RpcApi {
-        url: "https://ic-alloy-evm-rpc-proxy.kristofer-977.workers.dev/eth-sepolia".to_string(),
-        headers: None,
-    })
-}
+    #[sol(rpc)]
+    interface AggregatorV3Interface {
+        function decimals() external view returns (uint8);
+
+        function description() external view returns (string memory);
+
+        function version() external view returns (uint256);
+
+        function getRoundData(uint80 _roundId) external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+
+    #[sol(rpc)]
+    interface IERC20Metadata {
+        function balanceOf(address account) external view returns (uint256);
+
+        function decimals() external view returns (uint8);
+
+        function symbol() external view returns (string memory);
+    }
+
+    #[sol(rpc)]
+    interface AggregatorProxy {
+        function phaseId() external view returns (uint16);
 
+        function phaseAggregators(uint16 phaseId) external view returns (address);
+    }
+}
 
-pub fn auth_guard() -> Result<(), String> {
+pub fn auth_guard() -> Result<(), BackendError> {
     match ic_cdk::caller() {
         caller if caller == Principal::anonymous() => {
-            Err("Calls with the anonymous principal are not allowed.".to_string())
+            Err(BackendError::Unauthorized("Calls with the anonymous principal are not allowed.".to_string()))
         }
         _ => Ok(()),
     }
 }
 
+pub fn controller_guard() -> Result<(), BackendError> {
+    let caller = ic_cdk::caller();
+    if ic_cdk::api::is_controller(&caller) {
+        Ok(())
+    } else {
+        Err(BackendError::Unauthorized("Only canister controllers can call this method.".to_string()))
+    }
+}
+
 
 pub fn create_derivation_path(principal: &Principal) -> Vec<Vec<u8>> {
     const SCHEMA_V1: u8 = 1;
@@ -159,29 +213,29 @@ pub fn get_ecdsa_key_name() -> String {
 
 
 // Fungsi untuk validasi dan normalisasi address
-pub fn validate_and_normalize_address(address_str: &str) -> Result<Address, String> {
+pub fn validate_and_normalize_address(address_str: &str) -> Result<Address, BackendError> {
     // Remove whitespace
     let trimmed = address_str.trim();
     
     // Check if empty
     if trimmed.is_empty() {
-        return Err("Address cannot be empty".to_string());
+        return Err(BackendError::InvalidAddress("Address cannot be empty".to_string()));
     }
     
     // Check basic format
     if !trimmed.starts_with("0x") {
-        return Err("Address must start with '0x'".to_string());
+        return Err(BackendError::InvalidAddress("Address must start with '0x'".to_string()));
     }
     
     // Check length (0x + 40 hex characters = 42 total)
     if trimmed.len() != 42 {
-        return Err(format!("Address must be 42 characters long, got {}", trimmed.len()));
+        return Err(BackendError::InvalidAddress(format!("Address must be 42 characters long, got {}", trimmed.len())));
     }
     
     // Check if all characters after 0x are hex
     let hex_part = &trimmed[2..];
     if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err("Address contains invalid hex characters".to_string());
+        return Err(BackendError::InvalidAddress("Address contains invalid hex characters".to_string()));
     }
     
     // Try to parse with alloy (this will validate checksum)
@@ -190,37 +244,35 @@ pub fn validate_and_normalize_address(address_str: &str) -> Result<Address, Stri
         Err(e) => {
             if e.to_string().contains("checksum") {
                 let lowercase_addr = format!("0x{}", hex_part.to_lowercase());
-                match Address::from_str(&lowercase_addr) {
-                    Ok(addr) => {
-                        println!("Warning: Address checksum invalid, using normalized address: {}", addr);
-                        Ok(addr)
-                    }
-                    Err(_) => Err(format!("Invalid address format: {}", e))
-                }
+                Address::from_str(&lowercase_addr)
+                    .map_err(|_| BackendError::InvalidAddress(format!("Invalid address format: {}", e)))
             } else {
-                Err(format!("Invalid address: {}", e))
+                Err(BackendError::InvalidAddress(e.to_string()))
             }
         }
     }
 }
 
-pub fn get_correct_address(address_str: &str) -> Result<String, String> {
+pub fn get_correct_address(address_str: &str) -> Result<String, BackendError> {
     let normalized = validate_and_normalize_address(address_str)?;
     Ok(format!("{:?}", normalized))
 }
 
-pub fn validate_eth_address(address: &str) -> Result<Address, String> {
+pub fn validate_eth_address(address: &str) -> Result<Address, BackendError> {
     match Address::from_str(address) {
         Ok(addr) => Ok(addr),
         Err(e) => {
             let error_str = e.to_string();
             if error_str.contains("checksum") {
                 match get_correct_address(address) {
-                    Ok(correct) => Err(format!("Invalid checksum. Use: {}", correct)),
-                    Err(_) => Err(format!("Invalid address: {}", error_str)),
+                    Ok(correct) => Err(BackendError::ChecksumMismatch {
+                        provided: address.to_string(),
+                        expected: correct,
+                    }),
+                    Err(_) => Err(BackendError::InvalidAddress(error_str)),
                 }
             } else {
-                Err(format!("Invalid Ethereum address: {}", error_str))
+                Err(BackendError::InvalidAddress(error_str))
             }
         }
     }
@@ -319,40 +371,12 @@ pub fn format_price(raw_price: I256, decimals: u8) -> String {
     }
 }
 
-pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<Uint<248, 4>, String> {
-    let parts: Vec<&str> = amount.split('.').collect();
-    match parts.len() {
-        1 => {
-            let integer = Uint::<248, 4>::from_str(parts[0])
-                .map_err(|e| format!("Invalid amount: {}", e))?;
-            integer.checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(decimals)))
-                .ok_or("Overflow in multiplication".to_string())
-        },
-        2 => {
-            let integer = Uint::<248, 4>::from_str(parts[0])
-                .map_err(|e| format!("Invalid integer part: {}", e))?
-                .checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(decimals)))
-                .ok_or("Overflow in multiplication".to_string())?;
-            
-            let fractional_str = if parts[1].len() > decimals as usize {
-                &parts[1][..decimals as usize]
-            } else {
-                parts[1]
-            };
-            
-            let fractional = Uint::<248, 4>::from_str(fractional_str)
-                .map_err(|e| format!("Invalid fractional part: {}", e))?
-                .checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(decimals - fractional_str.len() as u8)))
-                .ok_or("Overflow in fractional part".to_string())?;
-            
-            integer.checked_add(fractional)
-                .ok_or("Overflow in addition".to_string())
-        },
-        _ => Err("Invalid amount format".to_string()),
-    }
+/// Parse a decimal token amount into base units. Input with more fractional digits
+/// than the token has is rejected rather than truncated.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<Uint<248, 4>, BackendError> {
+    parse_fixed_point(amount, decimals as usize, "Amount")
 }
 
-
 pub fn format_token_price(price: I256, decimals: u8) -> (String, String) {
     let (price_abs, is_negative) = if price < I256::ZERO {
         (U256::from((-price).into_raw()), true)
@@ -398,35 +422,112 @@ pub fn format_with_decimals(value: Uint<248, 4>, decimals: u8) -> String {
     }
 }
 
-/// Parse USD value string into raw amount with 18 decimals
-pub fn parse_usd_value(usd_value: &str) -> Result<Uint<248, 4>, String> {
-    let parts: Vec<&str> = usd_value.split('.').collect();
-    match parts.len() {
-        1 => {
-            Uint::<248, 4>::from_str(parts[0])
-                .map_err(|e| format!("Invalid USD value: {}", e))?
-                .checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(18)))
-                .ok_or("Overflow in multiplication".to_string())
-        },
-        2 => {
-            let integer_part = Uint::<248, 4>::from_str(parts[0])
-                .map_err(|e| format!("Invalid integer part: {}", e))?
-                .checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(18)))
-                .ok_or("Overflow in multiplication".to_string())?;
-            
-            let fractional_part = if parts[1].len() > 18 {
-                Uint::<248, 4>::from_str(&parts[1][..18])
-                    .map_err(|e| format!("Invalid fractional part: {}", e))?
-            } else {
-                let padded = format!("{:0<18}", parts[1]);
-                Uint::<248, 4>::from_str(&padded)
-                    .map_err(|e| format!("Invalid fractional part: {}", e))?
-            };
-            
-            integer_part.checked_add(fractional_part)
-                .ok_or("Overflow in addition".to_string())
+/// Parse USD value string into raw amount with 18 decimals.
+///
+/// Only plain decimal notation is accepted, so the result is exact: negative values,
+/// NaN/infinity, scientific notation and more than 18 fractional digits are rejected.
+pub fn parse_usd_value(usd_value: &str) -> Result<Uint<248, 4>, BackendError> {
+    parse_fixed_point(usd_value, 18, "USD value")
+}
+
+/// Parse a plain non-negative decimal such as `12.34` into an integer scaled by
+/// `10^decimals`. `what` names the value in error messages.
+fn parse_fixed_point(value: &str, decimals: usize, what: &str) -> Result<Uint<248, 4>, BackendError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(BackendError::InvalidInput(format!("{} cannot be empty", what)));
+    }
+    if trimmed.starts_with('-') {
+        return Err(BackendError::InvalidInput(format!("{} cannot be negative: {}", what, trimmed)));
+    }
+    let lowercase = trimmed.trim_start_matches('+').to_lowercase();
+    if lowercase.starts_with("nan") || lowercase.starts_with("inf") {
+        return Err(BackendError::InvalidInput(format!("{} must be a finite number: {}", what, trimmed)));
+    }
+    if lowercase.contains('e') {
+        return Err(BackendError::InvalidInput(format!("Scientific notation is not supported: {}", trimmed)));
+    }
+
+    let (integer_str, fractional_str) = match trimmed.split_once('.') {
+        Some((integer, fractional)) => (integer, fractional),
+        None => (trimmed, ""),
+    };
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if integer_str.is_empty()
+        || !is_digits(integer_str)
+        || !is_digits(fractional_str)
+        || (trimmed.contains('.') && fractional_str.is_empty())
+    {
+        return Err(BackendError::InvalidInput(format!("{} must be a plain decimal number: {}", what, trimmed)));
+    }
+    if fractional_str.len() > decimals {
+        return Err(BackendError::InvalidInput(format!(
+            "{} has more than {} decimal places: {}",
+            what, decimals, trimmed
+        )));
+    }
+
+    let integer_part = Uint::<248, 4>::from_str(integer_str)
+        .map_err(|e| BackendError::InvalidInput(format!("Invalid integer part: {}", e)))?
+        .checked_mul(Uint::<248, 4>::from(10).pow(Uint::from(decimals)))
+        .ok_or(BackendError::Overflow("Overflow in multiplication".to_string()))?;
+
+    if decimals == 0 {
+        return Ok(integer_part);
+    }
+    let padded = format!("{:0<width$}", fractional_str, width = decimals);
+    let fractional_part = Uint::<248, 4>::from_str(&padded)
+        .map_err(|e| BackendError::InvalidInput(format!("Invalid fractional part: {}", e)))?;
+
+    integer_part.checked_add(fractional_part)
+        .ok_or(BackendError::Overflow("Overflow in addition".to_string()))
+}
+
+/// How a conversion rounds when the result is not a whole number of base units
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Toward zero, the caller never receives more than the exact amount
+    #[default]
+    Down,
+    Up,
+    /// To the nearest unit, halves away from zero
+    HalfUp,
+    /// To the nearest unit, halves to the even neighbour
+    HalfEven,
+}
+
+/// `value * numerator / denominator`, rounded with `rounding`. The product must fit in 248 bits.
+pub fn mul_div_rounded(
+    value: Uint<248, 4>,
+    numerator: Uint<248, 4>,
+    denominator: Uint<248, 4>,
+    rounding: RoundingMode,
+) -> Result<Uint<248, 4>, BackendError> {
+    if denominator.is_zero() {
+        return Err(BackendError::InvalidInput("Division by zero".to_string()));
+    }
+    let product = value.checked_mul(numerator)
+        .ok_or(BackendError::Overflow("Overflow in multiplication".to_string()))?;
+    let (quotient, remainder) = product.div_rem(denominator);
+    if remainder.is_zero() {
+        return Ok(quotient);
+    }
+
+    // Compare the remainder with half the denominator without doubling it
+    let round_up = match rounding {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::HalfUp | RoundingMode::HalfEven => match remainder.cmp(&(denominator - remainder)) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => rounding == RoundingMode::HalfUp || quotient.bit(0),
         },
-        _ => Err("Invalid USD value format".to_string()),
+    };
+    if round_up {
+        quotient.checked_add(Uint::from(1))
+            .ok_or(BackendError::Overflow("Overflow in rounding".to_string()))
+    } else {
+        Ok(quotient)
     }
 }
 
@@ -437,11 +538,8 @@ pub fn format_price_raw(raw_price: i128, decimals: u8) -> String {
     }
 
     let is_negative = raw_price < 0;
-    let abs_price = if is_negative {
-        -raw_price
-    } else {
-        raw_price
-    };
+    // Unsigned so that i128::MIN does not overflow
+    let abs_price = raw_price.unsigned_abs();
 
     let price_str = abs_price.to_string();
     let len = price_str.len();
@@ -458,3 +556,159 @@ pub fn format_price_raw(raw_price: i128, decimals: u8) -> String {
         format!("{}{}.{}", if is_negative { "-" } else { "" }, integer_part, decimal_part)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(value: &str) -> Uint<248, 4> {
+        parse_usd_value(value).unwrap()
+    }
+
+    fn is_invalid_input(value: &str) -> bool {
+        matches!(parse_usd_value(value), Err(BackendError::InvalidInput(_)))
+    }
+
+    #[test]
+    fn parse_usd_value_scales_to_18_decimals() {
+        let one = Uint::<248, 4>::from(10).pow(Uint::from(18));
+        assert_eq!(usd("1"), one);
+        assert_eq!(usd(" 12.34 "), Uint::from(1234) * one / Uint::from(100));
+        assert_eq!(usd("0.000000000000000001"), Uint::from(1));
+        assert_eq!(usd("1.000000000000000000"), one);
+        assert_eq!(usd("0"), Uint::ZERO);
+    }
+
+    #[test]
+    fn parse_usd_value_is_exact_past_f64_precision() {
+        assert_eq!(
+            usd("123456789012345678.123456789012345678"),
+            Uint::from_str("123456789012345678123456789012345678").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_usd_value_rejects_malformed_input() {
+        for value in ["", "  ", "-1", "NaN", "inf", "+Infinity", "1e18", "1E5", "1.", ".5", "1,5"] {
+            assert!(is_invalid_input(value), "{} should be rejected", value);
+        }
+        assert!(is_invalid_input("0x10"));
+        assert!(is_invalid_input("abc"));
+    }
+
+    #[test]
+    fn parse_usd_value_rejects_more_than_18_decimals() {
+        assert!(is_invalid_input("1.0000000000000000001"));
+    }
+
+    #[test]
+    fn parse_usd_value_reports_overflow() {
+        let huge = format!("1{}", "0".repeat(70));
+        assert!(matches!(parse_usd_value(&huge), Err(BackendError::Overflow(_))));
+    }
+
+    fn is_invalid_amount(amount: &str, decimals: u8) -> bool {
+        matches!(parse_token_amount(amount, decimals), Err(BackendError::InvalidInput(_)))
+    }
+
+    #[test]
+    fn parse_token_amount_scales_to_token_decimals() {
+        assert_eq!(parse_token_amount("1.5", 6).unwrap(), Uint::from(1_500_000));
+        assert_eq!(parse_token_amount("0.000001", 6).unwrap(), Uint::from(1));
+        assert_eq!(parse_token_amount("42", 0).unwrap(), Uint::from(42));
+        assert_eq!(parse_token_amount(" 2 ", 2).unwrap(), Uint::from(200));
+    }
+
+    #[test]
+    fn parse_token_amount_rejects_more_decimals_than_the_token() {
+        assert!(is_invalid_amount("0.0000001", 6));
+        assert!(is_invalid_amount("1.5", 0));
+    }
+
+    #[test]
+    fn parse_token_amount_rejects_non_decimal_input() {
+        for amount in ["", "0x10", "0b1", "-1", "1e6", "1.", ".5", "1.2.3", "abc", "NaN"] {
+            assert!(is_invalid_amount(amount, 18), "{} should be rejected", amount);
+        }
+    }
+
+    fn mul_div(value: u64, numerator: u64, denominator: u64, rounding: RoundingMode) -> u64 {
+        mul_div_rounded(
+            Uint::from(value),
+            Uint::from(numerator),
+            Uint::from(denominator),
+            rounding,
+        )
+        .unwrap()
+        .to::<u64>()
+    }
+
+    #[test]
+    fn mul_div_rounded_is_exact_without_remainder() {
+        for rounding in [
+            RoundingMode::Down,
+            RoundingMode::Up,
+            RoundingMode::HalfUp,
+            RoundingMode::HalfEven,
+        ] {
+            assert_eq!(mul_div(6, 2, 3, rounding), 4);
+        }
+    }
+
+    #[test]
+    fn mul_div_rounded_below_half() {
+        // 10 / 3 = 3.33
+        assert_eq!(mul_div(10, 1, 3, RoundingMode::Down), 3);
+        assert_eq!(mul_div(10, 1, 3, RoundingMode::Up), 4);
+        assert_eq!(mul_div(10, 1, 3, RoundingMode::HalfUp), 3);
+        assert_eq!(mul_div(10, 1, 3, RoundingMode::HalfEven), 3);
+    }
+
+    #[test]
+    fn mul_div_rounded_above_half() {
+        // 20 / 3 = 6.67
+        assert_eq!(mul_div(20, 1, 3, RoundingMode::Down), 6);
+        assert_eq!(mul_div(20, 1, 3, RoundingMode::Up), 7);
+        assert_eq!(mul_div(20, 1, 3, RoundingMode::HalfUp), 7);
+        assert_eq!(mul_div(20, 1, 3, RoundingMode::HalfEven), 7);
+    }
+
+    #[test]
+    fn mul_div_rounded_exact_halves() {
+        // 5 / 2 = 2.5 and 7 / 2 = 3.5, half even goes to 2 and 4
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::Down), 2);
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::Up), 3);
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::HalfUp), 3);
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::HalfEven), 2);
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::HalfUp), 4);
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::HalfEven), 4);
+    }
+
+    #[test]
+    fn mul_div_rounded_rejects_zero_denominator() {
+        let result = mul_div_rounded(Uint::from(1), Uint::from(1), Uint::ZERO, RoundingMode::Down);
+        assert!(matches!(result, Err(BackendError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn mul_div_rounded_reports_overflow() {
+        let result = mul_div_rounded(Uint::MAX, Uint::from(2), Uint::from(3), RoundingMode::Down);
+        assert!(matches!(result, Err(BackendError::Overflow(_))));
+    }
+
+    #[test]
+    fn format_price_raw_places_the_decimal_point() {
+        assert_eq!(format_price_raw(123_456, 2), "1234.56");
+        assert_eq!(format_price_raw(5, 3), "0.005");
+        assert_eq!(format_price_raw(-5, 3), "-0.005");
+        assert_eq!(format_price_raw(42, 0), "42");
+    }
+
+    #[test]
+    fn format_price_raw_handles_i128_min() {
+        assert_eq!(
+            format_price_raw(i128::MIN, 8),
+            "-1701411834604692317316873037158.84105728"
+        );
+    }
+}
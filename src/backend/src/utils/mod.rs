@@ -1 +1,16 @@
-pub mod helper;
\ No newline at end of file
+pub mod config;
+pub mod consensus;
+pub mod erc20;
+pub mod error;
+pub mod fee_oracle;
+pub mod feed_validation;
+pub mod guard;
+pub mod helper;
+pub mod nonce_manager;
+pub mod price_cache;
+pub mod price_feed;
+pub mod price_history;
+pub mod revert;
+pub mod twap;
+pub mod tx_submitter;
+pub mod tx_tracker;
@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+
+use alloy::{
+    primitives::Address,
+    providers::{Provider, ProviderBuilder},
+    transports::icp::IcpConfig,
+};
+use ic_stable_structures::StableBTreeMap;
+
+use crate::memory::{get_memory, Memory, ADDRESS_NONCES_MEMORY_ID};
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+
+thread_local! {
+    // Next nonce to use per (chain id, derived EVM address). Kept in stable memory
+    // so it survives upgrades and is shared by every write endpoint. Nonces cached
+    // under the retired per-address layout are not carried over, the first
+    // reservation per address reads them from the chain again.
+    static ADDRESS_NONCES: RefCell<StableBTreeMap<(u64, [u8; 20]), u64, Memory>> =
+        RefCell::new(StableBTreeMap::init(get_memory(ADDRESS_NONCES_MEMORY_ID)));
+}
+
+fn nonce_key(network: &Network, address: Address) -> (u64, [u8; 20]) {
+    (network.chain_id, address.0 .0)
+}
+
+/// Reserve the next nonce for `address` on `network`.
+///
+/// The first call for an address fetches the transaction count from the chain,
+/// later calls are served from stable memory. The reservation is made before the
+/// next await point, so concurrent calls never receive the same nonce.
+pub async fn reserve_nonce(network: &Network, address: Address) -> Result<u64, BackendError> {
+    reserve_nonces(network, address, 1).await
+}
+
+/// Reserve `count` consecutive nonces for `address` on `network` and return the first.
+/// Give back the ones that end up unused with `release_nonces`.
+pub async fn reserve_nonces(
+    network: &Network,
+    address: Address,
+    count: u64,
+) -> Result<u64, BackendError> {
+    let fetched = if get_nonce(network, address).is_some() {
+        None
+    } else {
+        Some(fetch_transaction_count(network, address).await?)
+    };
+
+    Ok(take_nonces(network, address, fetched, count))
+}
+
+// The reservation itself, made without an await point in between
+fn take_nonces(network: &Network, address: Address, fetched: Option<u64>, count: u64) -> u64 {
+    ADDRESS_NONCES.with_borrow_mut(|nonces| {
+        let key = nonce_key(network, address);
+        let nonce = match (nonces.get(&key), fetched) {
+            (Some(cached), Some(fetched)) => cached.max(fetched),
+            (Some(cached), None) => cached,
+            (None, Some(fetched)) => fetched,
+            (None, None) => 0,
+        };
+        nonces.insert(key, nonce + count);
+        nonce
+    })
+}
+
+/// Give back `nonce` after it went unused, see `release_nonces`.
+pub fn release_nonce(network: &Network, address: Address, nonce: u64) {
+    release_nonces(network, address, nonce, nonce + 1);
+}
+
+/// Give back the unused nonces `first..reserved_until` of a reservation. The counter
+/// only rolls back to `first` while nothing was reserved after them, otherwise those
+/// later nonces would be handed out twice; the gap left then is closed by
+/// `resync_nonce` or a replacement transaction.
+pub fn release_nonces(network: &Network, address: Address, first: u64, reserved_until: u64) {
+    ADDRESS_NONCES.with_borrow_mut(|nonces| {
+        let key = nonce_key(network, address);
+        if nonces.get(&key) == Some(reserved_until) {
+            nonces.insert(key, first);
+        }
+    });
+}
+
+/// After a send that may have reached the node despite failing, replace the reservation
+/// ending at `reserved_until` with the pending transaction count, unless later nonces
+/// were reserved in the meantime. The count includes the transaction if it was broadcast.
+pub async fn resync_reserved_nonces(
+    network: &Network,
+    address: Address,
+    reserved_until: u64,
+) -> Result<(), BackendError> {
+    let count = fetch_transaction_count(network, address).await?;
+    release_nonces(network, address, count, reserved_until);
+    Ok(())
+}
+
+/// Overwrite the cached nonce for `address` with the transaction count on chain,
+/// including transactions still pending in the mempool.
+pub async fn resync_nonce(network: &Network, address: Address) -> Result<u64, BackendError> {
+    let count = fetch_transaction_count(network, address).await?;
+    ADDRESS_NONCES.with_borrow_mut(|nonces| {
+        nonces.insert(nonce_key(network, address), count);
+    });
+    Ok(count)
+}
+
+/// Next nonce cached for `address`, if any.
+pub fn get_nonce(network: &Network, address: Address) -> Option<u64> {
+    ADDRESS_NONCES.with_borrow(|nonces| nonces.get(&nonce_key(network, address)))
+}
+
+async fn fetch_transaction_count(
+    network: &Network,
+    address: Address,
+) -> Result<u64, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    // Pending transactions already hold their nonces, `latest` would hand them out again
+    provider
+        .get_transaction_count(address)
+        .pending()
+        .await
+        .map_err(BackendError::from_call_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::transports::icp::{RpcApi, RpcService};
+
+    fn network(chain_id: u64) -> Network {
+        Network {
+            name: "test".to_string(),
+            rpc_service: RpcService::Custom(RpcApi {
+                url: "https://localhost".to_string(),
+                headers: None,
+            }),
+            chain_id,
+            registry_address: Address::ZERO,
+            confirmations: 1,
+            consensus: None,
+        }
+    }
+
+    #[test]
+    fn failed_batch_send_rolls_back_to_first_unsent_nonce() {
+        let network = network(1);
+        let address = Address::repeat_byte(1);
+
+        let first_nonce = take_nonces(&network, address, Some(7), 5);
+        assert_eq!(first_nonce, 7);
+
+        // Items 0 and 1 went out, sending item 2 failed
+        release_nonces(&network, address, first_nonce + 2, first_nonce + 5);
+
+        assert_eq!(take_nonces(&network, address, None, 1), first_nonce + 2);
+    }
+
+    #[test]
+    fn release_keeps_nonces_reserved_after_the_batch() {
+        let network = network(2);
+        let address = Address::repeat_byte(2);
+
+        let first_nonce = take_nonces(&network, address, Some(0), 3);
+        let later = take_nonces(&network, address, None, 1);
+        release_nonces(&network, address, first_nonce + 1, first_nonce + 3);
+
+        assert_eq!(later, 3);
+        assert_eq!(take_nonces(&network, address, None, 1), 4);
+    }
+
+    #[test]
+    fn release_nonce_returns_a_single_unused_nonce() {
+        let network = network(3);
+        let address = Address::repeat_byte(3);
+
+        let nonce = take_nonces(&network, address, Some(4), 1);
+        release_nonce(&network, address, nonce);
+
+        assert_eq!(take_nonces(&network, address, None, 1), nonce);
+    }
+}
@@ -0,0 +1,287 @@
+use std::{borrow::Cow, cell::RefCell, time::Duration};
+
+use alloy::primitives::Address;
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_cdk_timers::TimerId;
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
+
+use crate::memory::{get_memory, Memory, PRICE_CACHE_MEMORY_ID, PRICE_REFRESH_CONFIG_MEMORY_ID};
+use crate::service::get_all_assets_with_prices::{fetch_all_assets_with_prices, AssetWithPrice};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::error::BackendError;
+use crate::utils::guard::FlagGuard;
+use crate::utils::helper::validate_eth_address;
+use crate::utils::price_history::record_prices;
+
+pub const MIN_REFRESH_INTERVAL_SECONDS: u64 = 60;
+pub const MAX_REFRESH_OWNERS: usize = 20;
+// Live reads of untracked owners replace the oldest untracked entry beyond this many
+pub const MAX_CACHE_ENTRIES: u64 = 500;
+// Cached prices of a tracked owner count as current for this many refresh intervals
+pub const CACHE_TOLERANCE_INTERVALS: u64 = 3;
+// Maximum age of cached prices the refresher does not renew
+pub const MAX_UNREFRESHED_CACHE_AGE_SECONDS: u64 = 15 * 60;
+
+/// Which owners the background refresher keeps prices for, and how often
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct PriceRefreshConfig {
+    pub owners: Vec<String>,
+    pub network: Option<String>,
+    pub interval_seconds: u64,
+}
+
+/// Last `getAllAssetsWithPrices` result stored for one owner on one network
+#[derive(CandidType, Deserialize, Clone)]
+pub struct CachedPrices {
+    pub network: String,
+    pub owner_address: String,
+    pub assets: Vec<AssetWithPrice>,
+    /// Unix time in seconds of the fetch that produced `assets`
+    pub fetched_at: u64,
+    /// Error of the latest refresh, if it failed after `fetched_at`
+    pub last_error: Option<String>,
+}
+
+impl Storable for PriceRefreshConfig {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("failed to encode price refresh config"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("failed to decode price refresh config")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for CachedPrices {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("failed to encode cached prices"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("failed to decode cached prices")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+thread_local! {
+    static REFRESH_CONFIG: RefCell<StableCell<PriceRefreshConfig, Memory>> = RefCell::new(
+        StableCell::init(get_memory(PRICE_REFRESH_CONFIG_MEMORY_ID), PriceRefreshConfig::default())
+            .expect("failed to initialize price refresh config"),
+    );
+
+    // Keyed by `cache_key(network, owner)`
+    static PRICE_CACHE: RefCell<StableBTreeMap<String, CachedPrices, Memory>> =
+        RefCell::new(StableBTreeMap::init(get_memory(PRICE_CACHE_MEMORY_ID)));
+
+    // Timers do not survive upgrades, so these live on the heap
+    static REFRESH_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+    static REFRESH_IN_PROGRESS: RefCell<bool> = const { RefCell::new(false) };
+}
+
+fn cache_key(network: &str, owner_address: Address) -> String {
+    format!("{}/{:?}", network, owner_address)
+}
+
+pub fn now_seconds() -> u64 {
+    ic_cdk::api::time() / 1_000_000_000
+}
+
+pub fn get_refresh_config() -> PriceRefreshConfig {
+    REFRESH_CONFIG.with_borrow(|config| config.get().clone())
+}
+
+/// Validate and store a new refresher configuration, then restart the timer with it.
+/// An interval of 0 disables the refresher.
+pub fn set_refresh_config(config: PriceRefreshConfig) -> Result<(), BackendError> {
+    if config.interval_seconds != 0 && config.interval_seconds < MIN_REFRESH_INTERVAL_SECONDS {
+        return Err(BackendError::InvalidInput(format!(
+            "Refresh interval must be 0 or at least {} seconds",
+            MIN_REFRESH_INTERVAL_SECONDS
+        )));
+    }
+    if config.owners.len() > MAX_REFRESH_OWNERS {
+        return Err(BackendError::InvalidInput(format!(
+            "Maximum {} owners can be refreshed",
+            MAX_REFRESH_OWNERS
+        )));
+    }
+    let owners = config
+        .owners
+        .iter()
+        .map(|owner| validate_eth_address(owner).map(|address| format!("{:?}", address)))
+        .collect::<Result<Vec<_>, BackendError>>()?;
+    let network = resolve_network(config.network)?;
+
+    let config = PriceRefreshConfig {
+        owners,
+        network: Some(network.name),
+        interval_seconds: config.interval_seconds,
+    };
+    REFRESH_CONFIG.with_borrow_mut(|cell| {
+        cell.set(config).expect("failed to store price refresh config");
+    });
+    start_refresh_timer();
+    Ok(())
+}
+
+/// (Re)start the refresh timer from the stored configuration.
+/// Called after install, after every upgrade and whenever the configuration changes.
+pub fn start_refresh_timer() {
+    if let Some(timer_id) = REFRESH_TIMER.with_borrow_mut(|timer| timer.take()) {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+
+    let config = get_refresh_config();
+    if config.interval_seconds == 0 || config.owners.is_empty() {
+        return;
+    }
+
+    let timer_id = ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(config.interval_seconds),
+        || ic_cdk::spawn(refresh_prices()),
+    );
+    REFRESH_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+/// Timer entry point; a tick that finds a refresh still running is skipped.
+pub async fn refresh_prices() {
+    if let Err(e) = try_refresh_prices().await {
+        ic_cdk::println!("Skipping price refresh: {}", e);
+    }
+}
+
+/// Fetch prices for every configured owner and store them in the cache.
+/// A failed fetch keeps the previous prices and records the error next to them.
+/// Fails without refreshing anything while another refresh is running.
+pub async fn try_refresh_prices() -> Result<(), BackendError> {
+    let Some(_refreshing) = FlagGuard::acquire(&REFRESH_IN_PROGRESS) else {
+        return Err(BackendError::InvalidInput("refresh already in progress".to_string()));
+    };
+
+    let config = get_refresh_config();
+    match resolve_network(config.network) {
+        Ok(network) => {
+            for owner in config.owners {
+                match validate_eth_address(&owner) {
+                    Ok(owner_address) => refresh_owner(&network, owner_address).await,
+                    Err(e) => ic_cdk::println!("Skipping price refresh for {}: {}", owner, e),
+                }
+            }
+        }
+        Err(e) => ic_cdk::println!("Skipping price refresh: {}", e),
+    }
+    Ok(())
+}
+
+async fn refresh_owner(network: &Network, owner_address: Address) {
+    let result = fetch_all_assets_with_prices(network, owner_address).await;
+    match result {
+        Ok(assets) => store_prices(network, owner_address, assets),
+        Err(e) => store_refresh_error(network, owner_address, e.to_string()),
+    }
+}
+
+pub fn is_tracked(network: &Network, owner_address: Address) -> bool {
+    let config = get_refresh_config();
+    config.network.as_deref() == Some(network.name.as_str())
+        && config.owners.contains(&format!("{:?}", owner_address))
+}
+
+/// Store freshly fetched prices and add them to the price history. Once the cache is
+/// full, an owner the refresher does not track takes the place of the untracked owner
+/// fetched longest ago, so arbitrary callers cannot grow it unbounded.
+pub fn store_prices(network: &Network, owner_address: Address, assets: Vec<AssetWithPrice>) {
+    record_prices(network, owner_address, &assets);
+
+    let key = cache_key(&network.name, owner_address);
+    PRICE_CACHE.with_borrow_mut(|cache| {
+        if !cache.contains_key(&key)
+            && cache.len() >= MAX_CACHE_ENTRIES
+            && !is_tracked(network, owner_address)
+        {
+            // Read the configuration once, not per entry
+            let config = get_refresh_config();
+            let oldest = cache
+                .iter()
+                .filter(|(_, cached)| {
+                    config.network.as_deref() != Some(cached.network.as_str())
+                        || !config.owners.contains(&cached.owner_address)
+                })
+                .min_by_key(|(_, cached)| cached.fetched_at)
+                .map(|(key, _)| key);
+            match oldest {
+                Some(oldest) => {
+                    cache.remove(&oldest);
+                }
+                None => return,
+            }
+        }
+        cache.insert(
+            key,
+            CachedPrices {
+                network: network.name.clone(),
+                owner_address: format!("{:?}", owner_address),
+                assets,
+                fetched_at: now_seconds(),
+                last_error: None,
+            },
+        );
+    });
+}
+
+/// How old cached prices of `owner_address` may be before queries treat them as stale:
+/// a few refresh intervals for owners the refresher tracks, a fixed bound otherwise.
+pub fn max_cache_age(network: &Network, owner_address: Address) -> u64 {
+    let config = get_refresh_config();
+    if config.interval_seconds > 0 && is_tracked(network, owner_address) {
+        config.interval_seconds.saturating_mul(CACHE_TOLERANCE_INTERVALS)
+    } else {
+        MAX_UNREFRESHED_CACHE_AGE_SECONDS
+    }
+}
+
+fn store_refresh_error(network: &Network, owner_address: Address, error: String) {
+    let key = cache_key(&network.name, owner_address);
+    PRICE_CACHE.with_borrow_mut(|cache| {
+        let entry = match cache.get(&key) {
+            Some(previous) => CachedPrices {
+                last_error: Some(error),
+                ..previous
+            },
+            None => CachedPrices {
+                network: network.name.clone(),
+                owner_address: format!("{:?}", owner_address),
+                assets: Vec::new(),
+                fetched_at: 0,
+                last_error: Some(error),
+            },
+        };
+        cache.insert(key, entry);
+    });
+}
+
+pub fn get_cached_prices(network: &Network, owner_address: Address) -> Option<CachedPrices> {
+    PRICE_CACHE.with_borrow(|cache| cache.get(&cache_key(&network.name, owner_address)))
+}
+
+/// Cached assets of an owner for the state-backed query endpoints.
+/// Fails when nothing has been fetched yet, pointing at the matching live call.
+pub fn require_cached_assets(
+    owner_address: &str,
+    network: Option<String>,
+    live_method: &str,
+) -> Result<CachedPrices, BackendError> {
+    let owner_addr = validate_eth_address(owner_address)?;
+    let network = resolve_network(network)?;
+
+    match get_cached_prices(&network, owner_addr) {
+        Some(cached) if cached.fetched_at > 0 => Ok(cached),
+        _ => Err(BackendError::AssetNotFound(format!(
+            "No cached prices for {} on {}, call {} first",
+            owner_address, network.name, live_method
+        ))),
+    }
+}
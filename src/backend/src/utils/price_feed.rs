@@ -0,0 +1,431 @@
+use alloy::{
+    primitives::{Address, Uint, I256, U256},
+    providers::ProviderBuilder,
+    transports::icp::IcpConfig,
+};
+
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::{AggregatorProxy, AggregatorV3Interface, AssetPriceRegistry};
+
+// Used when the caller does not pass a staleness threshold
+pub const DEFAULT_STALE_PRICE_THRESHOLD_SECONDS: u64 = 3_600;
+// Proxy round ids carry the phase in the bits above the aggregator round id
+pub const PHASE_OFFSET: u32 = 64;
+// Rounds returned by one walk back, each one is an HTTPS outcall
+pub const MAX_ROUNDS_PER_WALK: u32 = 50;
+// Missing rounds one step back skips before giving up, each one is an HTTPS outcall
+pub const MAX_MISSING_ROUNDS: u32 = 20;
+
+/// `decimals()` and `description()` of a feed, `None` where the call reverted
+#[derive(Clone, Debug)]
+pub struct FeedMetadata {
+    pub decimals: Option<u8>,
+    pub description: Option<String>,
+}
+
+/// One round of a Chainlink aggregator
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundData {
+    pub round_id: u128,
+    pub answer: i128,
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub answered_in_round: u128,
+}
+
+pub async fn latest_round(network: &Network, feed: Address) -> Result<RoundData, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let aggregator = AggregatorV3Interface::new(feed, provider);
+    let round = aggregator
+        .latestRoundData()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    to_round_data(
+        round.roundId,
+        round.answer,
+        round.startedAt,
+        round.updatedAt,
+        round.answeredInRound,
+    )
+}
+
+pub async fn round_data(
+    network: &Network,
+    feed: Address,
+    round_id: u128,
+) -> Result<RoundData, BackendError> {
+    let round_id = Uint::<80, 2>::try_from(round_id)
+        .map_err(|_| BackendError::Overflow(format!("round id {} is not a uint80", round_id)))?;
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let aggregator = AggregatorV3Interface::new(feed, provider);
+    let round = aggregator
+        .getRoundData(round_id)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    to_round_data(
+        round.roundId,
+        round.answer,
+        round.startedAt,
+        round.updatedAt,
+        round.answeredInRound,
+    )
+}
+
+/// Like `round_data`, but `None` when the round does not exist. Aggregators either
+/// revert or return an empty round for ids they never reached.
+pub async fn try_round_data(
+    network: &Network,
+    feed: Address,
+    round_id: u128,
+) -> Result<Option<RoundData>, BackendError> {
+    match round_data(network, feed, round_id).await {
+        Ok(round) if round.updated_at == 0 => Ok(None),
+        Ok(round) => Ok(Some(round)),
+        Err(BackendError::ContractRevert { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn phase_id(round_id: u128) -> u16 {
+    (round_id >> PHASE_OFFSET) as u16
+}
+
+pub fn aggregator_round_id(round_id: u128) -> u64 {
+    round_id as u64
+}
+
+pub fn compose_round_id(phase_id: u16, aggregator_round_id: u64) -> u128 {
+    ((phase_id as u128) << PHASE_OFFSET) | aggregator_round_id as u128
+}
+
+/// Proxy round id of the last round of `phase_id`, `None` if the proxy never had
+/// that phase. Earlier phases point at retired aggregators, which stopped at their
+/// latest round.
+pub async fn phase_last_round_id(
+    network: &Network,
+    feed: Address,
+    phase_id: u16,
+) -> Result<Option<u128>, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let proxy = AggregatorProxy::new(feed, provider.clone());
+    let aggregator = proxy
+        .phaseAggregators(phase_id)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?
+        ._0;
+    if aggregator == Address::ZERO {
+        return Ok(None);
+    }
+
+    let round = AggregatorV3Interface::new(aggregator, provider)
+        .latestRoundData()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    let last = round.roundId.to::<u128>();
+    if last == 0 {
+        return Ok(None);
+    }
+    Ok(Some(compose_round_id(phase_id, last as u64)))
+}
+
+/// The round before `round_id`. Rounds missing from a phase are skipped down to its
+/// first round, then the walk continues at the last round of the previous phase.
+/// `None` once the first phase is exhausted.
+pub async fn previous_round(
+    network: &Network,
+    feed: Address,
+    round_id: u128,
+) -> Result<Option<RoundData>, BackendError> {
+    let mut phase = phase_id(round_id);
+    let mut aggregator_round = aggregator_round_id(round_id);
+    let mut missing = 0;
+
+    loop {
+        while aggregator_round > 1 {
+            aggregator_round -= 1;
+            let candidate = compose_round_id(phase, aggregator_round);
+            if let Some(round) = try_round_data(network, feed, candidate).await? {
+                return Ok(Some(round));
+            }
+            missing += 1;
+            if missing >= MAX_MISSING_ROUNDS {
+                return Err(BackendError::AssetNotFound(format!(
+                    "Feed {:?} is missing {} rounds before {}",
+                    feed, missing, round_id
+                )));
+            }
+        }
+
+        if phase <= 1 {
+            return Ok(None);
+        }
+        phase -= 1;
+        // One past the last round, so the loop above starts with it
+        aggregator_round = match phase_last_round_id(network, feed, phase).await? {
+            Some(last) => aggregator_round_id(last).saturating_add(1),
+            None => 1,
+        };
+    }
+}
+
+/// Up to `count` rounds, newest first, starting at `from_round_id` or at the latest round.
+pub async fn walk_back_rounds(
+    network: &Network,
+    feed: Address,
+    from_round_id: Option<u128>,
+    count: u32,
+) -> Result<Vec<RoundData>, BackendError> {
+    if count == 0 || count > MAX_ROUNDS_PER_WALK {
+        return Err(BackendError::InvalidInput(format!(
+            "Round count must be between 1 and {}",
+            MAX_ROUNDS_PER_WALK
+        )));
+    }
+
+    let mut round = match from_round_id {
+        Some(round_id) => try_round_data(network, feed, round_id)
+            .await?
+            .ok_or_else(|| missing_round(feed, round_id))?,
+        None => latest_round(network, feed).await?,
+    };
+    let mut rounds = Vec::new();
+    loop {
+        let round_id = round.round_id;
+        rounds.push(round);
+        if rounds.len() >= count as usize {
+            break;
+        }
+        match previous_round(network, feed, round_id).await? {
+            Some(previous) => round = previous,
+            None => break,
+        }
+    }
+    Ok(rounds)
+}
+
+/// The round that was current at `timestamp`: the last one updated at or before it.
+///
+/// Phases are checked newest first by their first round, then the matching phase is
+/// binary searched, so a lookup costs a few outcalls per phase plus log2 of its rounds.
+pub async fn round_at(
+    network: &Network,
+    feed: Address,
+    timestamp: u64,
+) -> Result<RoundData, BackendError> {
+    let latest = latest_round(network, feed).await?;
+    if latest.updated_at <= timestamp {
+        return Ok(latest);
+    }
+
+    let not_found = || {
+        BackendError::AssetNotFound(format!(
+            "Feed {:?} has no round at or before {}",
+            feed, timestamp
+        ))
+    };
+
+    // The last round of `phase` is known to be newer than `timestamp`
+    let mut phase = phase_id(latest.round_id);
+    let mut last = aggregator_round_id(latest.round_id);
+    loop {
+        if let Some(first) = try_round_data(network, feed, compose_round_id(phase, 1)).await? {
+            if first.updated_at <= timestamp {
+                return search_phase(network, feed, timestamp, phase, first, last).await;
+            }
+        }
+
+        if phase <= 1 {
+            return Err(not_found());
+        }
+        phase -= 1;
+        let last_round_id = phase_last_round_id(network, feed, phase)
+            .await?
+            .ok_or_else(not_found)?;
+        let Some(last_round) = try_round_data(network, feed, last_round_id).await? else {
+            return Err(not_found());
+        };
+        if last_round.updated_at <= timestamp {
+            return Ok(last_round);
+        }
+        last = aggregator_round_id(last_round_id);
+    }
+}
+
+// `low` is at or before `timestamp` and aggregator round `high` after it
+async fn search_phase(
+    network: &Network,
+    feed: Address,
+    timestamp: u64,
+    phase: u16,
+    mut low: RoundData,
+    mut high: u64,
+) -> Result<RoundData, BackendError> {
+    loop {
+        let low_id = aggregator_round_id(low.round_id);
+        if high - low_id <= 1 {
+            return Ok(low);
+        }
+        let middle = low_id + (high - low_id) / 2;
+        match try_round_data(network, feed, compose_round_id(phase, middle)).await? {
+            Some(round) if round.updated_at <= timestamp => low = round,
+            _ => high = middle,
+        }
+    }
+}
+
+fn missing_round(feed: Address, round_id: u128) -> BackendError {
+    BackendError::AssetNotFound(format!("Feed {:?} has no round {}", feed, round_id))
+}
+
+/// Price feed `owner_address` registered for `asset_address`. The registry only
+/// exposes assets by symbol, so the symbol is looked up first.
+pub async fn registered_feed(
+    network: &Network,
+    owner_address: Address,
+    asset_address: Address,
+) -> Result<Address, BackendError> {
+    let provider = ProviderBuilder::new()
+        .on_icp(IcpConfig::new(network.rpc_service.clone()).set_max_response_size(30_000));
+    let registry = AssetPriceRegistry::new(network.registry_address, provider);
+
+    let assets = registry
+        .getAllAssets(owner_address)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    let symbol = assets
+        ._0
+        .iter()
+        .position(|address| *address == asset_address)
+        .map(|index| assets._1[index].clone())
+        .ok_or_else(|| {
+            BackendError::AssetNotFound(format!(
+                "Asset {:?} is not registered for {:?}",
+                asset_address, owner_address
+            ))
+        })?;
+
+    let asset = registry
+        .getAssetBySymbol(owner_address, symbol)
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    Ok(asset.asset.priceFeed)
+}
+
+pub async fn feed_decimals(network: &Network, feed: Address) -> Result<u8, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let aggregator = AggregatorV3Interface::new(feed, provider);
+    let result = aggregator
+        .decimals()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    Ok(result._0)
+}
+
+pub async fn feed_description(network: &Network, feed: Address) -> Result<String, BackendError> {
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let aggregator = AggregatorV3Interface::new(feed, provider);
+    let result = aggregator
+        .description()
+        .call()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    Ok(result._0)
+}
+
+/// Read `decimals()` and `description()` of `feed` once, for checks that need both.
+/// Reverts are kept as `None`; transport failures are returned as errors.
+pub async fn feed_metadata(network: &Network, feed: Address) -> Result<FeedMetadata, BackendError> {
+    Ok(FeedMetadata {
+        decimals: reverted_as_none(feed_decimals(network, feed).await)?,
+        description: reverted_as_none(feed_description(network, feed).await)?,
+    })
+}
+
+pub fn reverted_as_none<T>(result: Result<T, BackendError>) -> Result<Option<T>, BackendError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(BackendError::ContractRevert { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Apply the checks `PriceFeedUtils._getPriceWithFallback` runs on chain: a positive
+/// answer, an answer from the round itself and an update no older than `stale_price_threshold`.
+pub fn validate_round(
+    feed: Address,
+    round: &RoundData,
+    stale_price_threshold: u64,
+    now: u64,
+) -> Result<(), BackendError> {
+    let stale = || BackendError::StalePrice {
+        asset: format!("{:?}", feed),
+    };
+    if round.answer <= 0 || round.answered_in_round < round.round_id {
+        return Err(stale());
+    }
+    if round.updated_at.saturating_add(stale_price_threshold) < now {
+        return Err(stale());
+    }
+    Ok(())
+}
+
+fn to_round_data(
+    round_id: Uint<80, 2>,
+    answer: I256,
+    started_at: U256,
+    updated_at: U256,
+    answered_in_round: Uint<80, 2>,
+) -> Result<RoundData, BackendError> {
+    Ok(RoundData {
+        round_id: round_id.to::<u128>(),
+        answer: answer
+            .try_into()
+            .map_err(|_| BackendError::Overflow("answer value out of range for i128".to_string()))?,
+        started_at: started_at
+            .try_into()
+            .map_err(|_| BackendError::Overflow("startedAt value too large for u64".to_string()))?,
+        updated_at: updated_at
+            .try_into()
+            .map_err(|_| BackendError::Overflow("updatedAt value too large for u64".to_string()))?,
+        answered_in_round: answered_in_round.to::<u128>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_id_splits_into_phase_and_aggregator_round() {
+        let round_id = (3u128 << 64) | 42;
+
+        assert_eq!(phase_id(round_id), 3);
+        assert_eq!(aggregator_round_id(round_id), 42);
+    }
+
+    #[test]
+    fn compose_round_id_inverts_the_split() {
+        let round_id = compose_round_id(5, 18_446_744_073_709_551_615);
+
+        assert_eq!(round_id, (5u128 << 64) | u64::MAX as u128);
+        assert_eq!(phase_id(round_id), 5);
+        assert_eq!(aggregator_round_id(round_id), u64::MAX);
+    }
+
+    #[test]
+    fn first_phase_round_ids() {
+        assert_eq!(compose_round_id(1, 1), 18_446_744_073_709_551_617);
+        assert_eq!(phase_id(18_446_744_073_709_551_617), 1);
+        assert_eq!(aggregator_round_id(18_446_744_073_709_551_617), 1);
+        assert_eq!(phase_id(1), 0);
+    }
+}
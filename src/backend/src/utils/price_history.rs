@@ -0,0 +1,472 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use alloy::primitives::Address;
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+
+use crate::memory::{
+    get_memory, Memory, PRICE_HISTORY_LENGTHS_MEMORY_ID, PRICE_HISTORY_MEMORY_ID,
+};
+use crate::service::get_all_assets_with_prices::AssetWithPrice;
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+use crate::utils::helper::format_price_raw;
+use crate::utils::price_cache::{is_tracked, now_seconds};
+
+// Points older than this are dropped when a series is written
+pub const HISTORY_RETENTION_SECONDS: u64 = 90 * 24 * 3_600;
+pub const MAX_POINTS_PER_SERIES: u64 = 2_000;
+// Owners the refresher does not track are only recorded below this many points
+pub const MAX_HISTORY_ENTRIES: u64 = 200_000;
+pub const MAX_POINTS_PER_QUERY: usize = 1_000;
+pub const MIN_CANDLE_INTERVAL_SECONDS: u64 = 60;
+pub const MAX_CANDLES: usize = 500;
+
+/// One observed price of an asset, keyed by the time the feed was updated
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct PricePoint {
+    /// Unix time in seconds of the feed update the price comes from
+    pub timestamp: u64,
+    pub price: String,
+    pub raw_price: String,
+    pub decimals: u8,
+    /// Unix time in seconds the canister read the price
+    pub observed_at: u64,
+}
+
+/// Open, high, low and close of the points in `[start, start + interval)`
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Candle {
+    pub start: u64,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub decimals: u8,
+    pub samples: u32,
+}
+
+impl Storable for PricePoint {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("failed to encode price point"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("failed to decode price point")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+thread_local! {
+    // Keyed by `point_key(series, timestamp)`, so each series is a contiguous, time ordered range
+    static PRICE_HISTORY: RefCell<StableBTreeMap<String, PricePoint, Memory>> =
+        RefCell::new(StableBTreeMap::init(get_memory(PRICE_HISTORY_MEMORY_ID)));
+
+    // Points per series key. Series written before this existed are counted on their next write.
+    static SERIES_LENGTHS: RefCell<StableBTreeMap<String, u64, Memory>> =
+        RefCell::new(StableBTreeMap::init(get_memory(PRICE_HISTORY_LENGTHS_MEMORY_ID)));
+}
+
+fn series_key(network: &str, owner_address: Address, asset_address: Address) -> String {
+    format!("{}/{:?}/{:?}/", network, owner_address, asset_address)
+}
+
+// Zero padded so that string order is time order
+fn point_key(series: &str, timestamp: u64) -> String {
+    format!("{}{:020}", series, timestamp)
+}
+
+/// `last_updated` is printed from a uint256, read its leading decimal digits
+fn parse_timestamp(last_updated: &str) -> Option<u64> {
+    let digits: String = last_updated
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok().filter(|timestamp| *timestamp > 0)
+}
+
+/// Failed feed reads show up as a zero or negative price or zero decimals, keep them
+/// out of the history so they do not skew candles and averages
+fn is_usable_price(asset: &AssetWithPrice) -> bool {
+    asset.decimals > 0
+        && asset
+            .raw_price
+            .trim()
+            .parse::<i128>()
+            .is_ok_and(|raw_price| raw_price > 0)
+}
+
+/// Record the prices of one `getAllAssetsWithPrices` read. Reading the same feed
+/// update twice overwrites a single point, so polling faster than the feed adds nothing.
+pub fn record_prices(network: &Network, owner_address: Address, assets: &[AssetWithPrice]) {
+    let observed_at = now_seconds();
+    let tracked = is_tracked(network, owner_address);
+
+    PRICE_HISTORY.with_borrow_mut(|history| {
+        for asset in assets {
+            if !is_usable_price(asset) {
+                continue;
+            }
+            let Ok(asset_address) = asset.address.parse::<Address>() else {
+                continue;
+            };
+            let series = series_key(&network.name, owner_address, asset_address);
+            let timestamp = parse_timestamp(&asset.last_updated).unwrap_or(observed_at);
+            let key = point_key(&series, timestamp);
+
+            let is_new = !history.contains_key(&key);
+            if !tracked && is_new && history.len() >= MAX_HISTORY_ENTRIES {
+                continue;
+            }
+            let mut length = series_length(history, &series);
+            if is_new {
+                length += 1;
+            }
+            history.insert(
+                key,
+                PricePoint {
+                    timestamp,
+                    price: asset.price.clone(),
+                    raw_price: asset.raw_price.clone(),
+                    decimals: asset.decimals,
+                    observed_at,
+                },
+            );
+            length = length.saturating_sub(prune_series(history, &series, length, observed_at));
+            SERIES_LENGTHS.with_borrow_mut(|lengths| lengths.insert(series, length));
+        }
+    });
+}
+
+// Counted with one scan the first time a series is written after an upgrade
+fn series_length(history: &StableBTreeMap<String, PricePoint, Memory>, series: &str) -> u64 {
+    SERIES_LENGTHS
+        .with_borrow(|lengths| lengths.get(&series.to_string()))
+        .unwrap_or_else(|| {
+            history
+                .range(series.to_string()..)
+                .take_while(|(key, _)| key.starts_with(series))
+                .count() as u64
+        })
+}
+
+// Drop points past the retention window, then the oldest beyond the per series cap.
+// Only the dropped points are visited. Returns how many were dropped.
+fn prune_series<M: ic_stable_structures::Memory>(
+    history: &mut StableBTreeMap<String, PricePoint, M>,
+    series: &str,
+    length: u64,
+    now: u64,
+) -> u64 {
+    let cutoff = point_key(series, now.saturating_sub(HISTORY_RETENTION_SECONDS));
+    let mut expired: Vec<String> = history
+        .range(series.to_string()..cutoff.clone())
+        .map(|(key, _)| key)
+        .collect();
+
+    let excess = length
+        .saturating_sub(expired.len() as u64)
+        .saturating_sub(MAX_POINTS_PER_SERIES);
+    if excess > 0 {
+        let oldest: Vec<String> = history
+            .range(cutoff..)
+            .take_while(|(key, _)| key.starts_with(series))
+            .map(|(key, _)| key)
+            .take(excess as usize)
+            .collect();
+        expired.extend(oldest);
+    }
+
+    for key in &expired {
+        history.remove(key);
+    }
+    expired.len() as u64
+}
+
+/// Points of a series with `from <= timestamp <= to`, oldest first, at most
+/// `MAX_POINTS_PER_QUERY`. Page through longer ranges from the last timestamp + 1.
+pub fn price_history(
+    network: &Network,
+    owner_address: Address,
+    asset_address: Address,
+    from: u64,
+    to: u64,
+) -> Result<Vec<PricePoint>, BackendError> {
+    if from > to {
+        return Err(BackendError::InvalidInput(format!(
+            "History range start {} is after its end {}",
+            from, to
+        )));
+    }
+    let series = series_key(&network.name, owner_address, asset_address);
+    Ok(PRICE_HISTORY.with_borrow(|history| {
+        history
+            .range(point_key(&series, from)..=point_key(&series, to))
+            .map(|(_, point)| point)
+            .take(MAX_POINTS_PER_QUERY)
+            .collect()
+    }))
+}
+
+/// Points a time weighted average over `[from, to]` needs: every point in the range
+/// and the last one before it, which was still current at `from`.
+pub fn window_points(
+    network: &Network,
+    owner_address: Address,
+    asset_address: Address,
+    from: u64,
+    to: u64,
+) -> Vec<PricePoint> {
+    let series = series_key(&network.name, owner_address, asset_address);
+    PRICE_HISTORY.with_borrow(|history| series_window(history, &series, from, to))
+}
+
+fn series_window<M: ic_stable_structures::Memory>(
+    history: &StableBTreeMap<String, PricePoint, M>,
+    series: &str,
+    from: u64,
+    to: u64,
+) -> Vec<PricePoint> {
+    let mut points: Vec<PricePoint> = history
+        .range(series.to_string()..point_key(series, from))
+        .map(|(_, point)| point)
+        .last()
+        .into_iter()
+        .collect();
+    points.extend(
+        history
+            .range(point_key(series, from)..=point_key(series, to))
+            .map(|(_, point)| point),
+    );
+    points
+}
+
+/// OHLC candles of `interval_seconds` over the retained history, oldest first and
+/// at most the latest `MAX_CANDLES`. Intervals without points have no candle.
+pub fn candles(
+    network: &Network,
+    owner_address: Address,
+    asset_address: Address,
+    interval_seconds: u64,
+) -> Result<Vec<Candle>, BackendError> {
+    if interval_seconds < MIN_CANDLE_INTERVAL_SECONDS {
+        return Err(BackendError::InvalidInput(format!(
+            "Candle interval must be at least {} seconds",
+            MIN_CANDLE_INTERVAL_SECONDS
+        )));
+    }
+    let series = series_key(&network.name, owner_address, asset_address);
+    let points: Vec<PricePoint> = PRICE_HISTORY.with_borrow(|history| {
+        history
+            .range(series.clone()..)
+            .take_while(|(key, _)| key.starts_with(&series))
+            .map(|(_, point)| point)
+            .collect()
+    });
+    build_candles(points, interval_seconds)
+}
+
+// `points` must be oldest first
+fn build_candles(
+    points: Vec<PricePoint>,
+    interval_seconds: u64,
+) -> Result<Vec<Candle>, BackendError> {
+    // Raw prices of the current candle: start, open, high, low, close, samples
+    let mut candles: Vec<Candle> = Vec::new();
+    let mut current: Option<(u64, i128, i128, i128, i128, u32)> = None;
+    let mut decimals = 0;
+    for point in points {
+        let raw_price: i128 = point
+            .raw_price
+            .parse()
+            .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
+        let start = point.timestamp - point.timestamp % interval_seconds;
+        current = match current {
+            Some((current_start, open, high, low, _, samples)) if current_start == start => Some((
+                start,
+                open,
+                high.max(raw_price),
+                low.min(raw_price),
+                raw_price,
+                samples + 1,
+            )),
+            previous => {
+                if let Some(candle) = previous {
+                    candles.push(to_candle(candle, decimals));
+                }
+                Some((start, raw_price, raw_price, raw_price, raw_price, 1))
+            }
+        };
+        decimals = point.decimals;
+    }
+    if let Some(candle) = current {
+        candles.push(to_candle(candle, decimals));
+    }
+
+    let skip = candles.len().saturating_sub(MAX_CANDLES);
+    Ok(candles.into_iter().skip(skip).collect())
+}
+
+fn to_candle(
+    (start, open, high, low, close, samples): (u64, i128, i128, i128, i128, u32),
+    decimals: u8,
+) -> Candle {
+    Candle {
+        start,
+        open: format_price_raw(open, decimals),
+        high: format_price_raw(high, decimals),
+        low: format_price_raw(low, decimals),
+        close: format_price_raw(close, decimals),
+        decimals,
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_stable_structures::VectorMemory;
+
+    const SERIES: &str = "test/0x01/0x02/";
+
+    fn history() -> StableBTreeMap<String, PricePoint, VectorMemory> {
+        StableBTreeMap::new(VectorMemory::default())
+    }
+
+    fn point(timestamp: u64, raw_price: i128) -> PricePoint {
+        PricePoint {
+            timestamp,
+            price: format_price_raw(raw_price, 2),
+            raw_price: raw_price.to_string(),
+            decimals: 2,
+            observed_at: timestamp,
+        }
+    }
+
+    fn insert(
+        history: &mut StableBTreeMap<String, PricePoint, VectorMemory>,
+        series: &str,
+        timestamp: u64,
+    ) {
+        history.insert(point_key(series, timestamp), point(timestamp, 100));
+    }
+
+    fn timestamps(
+        history: &StableBTreeMap<String, PricePoint, VectorMemory>,
+        series: &str,
+    ) -> Vec<u64> {
+        history
+            .range(series.to_string()..)
+            .take_while(|(key, _)| key.starts_with(series))
+            .map(|(_, point)| point.timestamp)
+            .collect()
+    }
+
+    #[test]
+    fn prune_series_drops_points_past_retention() {
+        let now = HISTORY_RETENTION_SECONDS + 1_000;
+        let cutoff = now - HISTORY_RETENTION_SECONDS;
+        let mut history = history();
+        for timestamp in [cutoff - 10, cutoff - 1, cutoff, now] {
+            insert(&mut history, SERIES, timestamp);
+        }
+        insert(&mut history, "test/0x01/0x03/", 1);
+
+        assert_eq!(prune_series(&mut history, SERIES, 4, now), 2);
+        assert_eq!(timestamps(&history, SERIES), vec![cutoff, now]);
+        assert_eq!(timestamps(&history, "test/0x01/0x03/"), vec![1]);
+    }
+
+    #[test]
+    fn prune_series_caps_points_per_series() {
+        let now = 1_000_000;
+        let length = MAX_POINTS_PER_SERIES + 3;
+        let mut history = history();
+        for timestamp in now - length + 1..=now {
+            insert(&mut history, SERIES, timestamp);
+        }
+
+        assert_eq!(prune_series(&mut history, SERIES, length, now), 3);
+        let remaining = timestamps(&history, SERIES);
+        assert_eq!(remaining.len() as u64, MAX_POINTS_PER_SERIES);
+        assert_eq!(remaining.first(), Some(&(now - length + 4)));
+        assert_eq!(prune_series(&mut history, SERIES, MAX_POINTS_PER_SERIES, now), 0);
+    }
+
+    #[test]
+    fn prune_series_counts_expired_points_towards_the_cap() {
+        let now = HISTORY_RETENTION_SECONDS + 10_000;
+        let length = MAX_POINTS_PER_SERIES + 1;
+        let mut history = history();
+        insert(&mut history, SERIES, 1);
+        for timestamp in now - MAX_POINTS_PER_SERIES + 1..=now {
+            insert(&mut history, SERIES, timestamp);
+        }
+
+        assert_eq!(prune_series(&mut history, SERIES, length, now), 1);
+        assert_eq!(timestamps(&history, SERIES).len() as u64, MAX_POINTS_PER_SERIES);
+    }
+
+    #[test]
+    fn window_includes_the_point_current_at_start() {
+        let mut history = history();
+        for timestamp in [10, 20, 30, 40, 50] {
+            insert(&mut history, SERIES, timestamp);
+        }
+        insert(&mut history, "test/0x01/0x03/", 25);
+
+        let window: Vec<u64> = series_window(&history, SERIES, 25, 40)
+            .iter()
+            .map(|point| point.timestamp)
+            .collect();
+        assert_eq!(window, vec![20, 30, 40]);
+
+        let window: Vec<u64> = series_window(&history, SERIES, 5, 15)
+            .iter()
+            .map(|point| point.timestamp)
+            .collect();
+        assert_eq!(window, vec![10]);
+    }
+
+    #[test]
+    fn window_of_empty_series_is_empty() {
+        let mut history = history();
+        insert(&mut history, "test/0x01/0x03/", 25);
+        assert!(series_window(&history, SERIES, 0, 100).is_empty());
+    }
+
+    #[test]
+    fn candles_keep_open_and_close_order() {
+        let points = vec![point(60, 300), point(70, 100), point(80, 500), point(90, 200)];
+        let candles = build_candles(points, 60).unwrap();
+
+        assert_eq!(candles.len(), 1);
+        let candle = &candles[0];
+        assert_eq!(candle.start, 60);
+        assert_eq!(candle.open, "3.00");
+        assert_eq!(candle.high, "5.00");
+        assert_eq!(candle.low, "1.00");
+        assert_eq!(candle.close, "2.00");
+        assert_eq!(candle.samples, 4);
+    }
+
+    #[test]
+    fn candles_skip_empty_intervals_and_split_on_edges() {
+        let points = vec![point(60, 100), point(119, 200), point(120, 300), point(300, 400)];
+        let candles = build_candles(points, 60).unwrap();
+
+        let starts: Vec<u64> = candles.iter().map(|candle| candle.start).collect();
+        assert_eq!(starts, vec![60, 120, 300]);
+        assert_eq!(candles[0].close, "2.00");
+        assert_eq!(candles[0].samples, 2);
+        assert_eq!(candles[1].open, "3.00");
+        assert_eq!(candles[1].samples, 1);
+    }
+
+    #[test]
+    fn candles_of_no_points_are_empty() {
+        assert!(build_candles(Vec::new(), 60).unwrap().is_empty());
+    }
+}
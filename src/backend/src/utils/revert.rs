@@ -0,0 +1,133 @@
+use alloy::{
+    primitives::Bytes,
+    sol_types::{Panic, Revert, SolError, SolInterface},
+    transports::TransportError,
+};
+
+use crate::utils::helper::AssetPriceRegistry::AssetPriceRegistryErrors;
+
+/// JSON-RPC error code nodes return for a reverted `eth_call` or `eth_estimateGas`
+const EXECUTION_REVERTED_CODE: i64 = 3;
+
+/// Errors that may carry the revert data of a failed `eth_call` or `eth_estimateGas`
+pub trait RevertData {
+    fn revert_data(&self) -> Option<Bytes>;
+
+    /// Whether the node answered with an execution-reverted error response
+    fn is_revert(&self) -> bool;
+}
+
+impl RevertData for TransportError {
+    fn revert_data(&self) -> Option<Bytes> {
+        self.as_error_resp()?.try_data_as::<Bytes>()?.ok()
+    }
+
+    fn is_revert(&self) -> bool {
+        self.as_error_resp().is_some_and(|payload| {
+            payload.code == EXECUTION_REVERTED_CODE
+                || payload.message.starts_with("execution reverted")
+        })
+    }
+}
+
+impl RevertData for alloy::contract::Error {
+    fn revert_data(&self) -> Option<Bytes> {
+        match self {
+            alloy::contract::Error::TransportError(e) => e.revert_data(),
+            _ => None,
+        }
+    }
+
+    fn is_revert(&self) -> bool {
+        match self {
+            alloy::contract::Error::TransportError(e) => e.is_revert(),
+            _ => false,
+        }
+    }
+}
+
+/// Decode revert data returned by the registry into a readable reason.
+///
+/// Handles `require` messages (`Error(string)`), compiler panics (`Panic(uint256)`)
+/// and the custom errors declared by the registry.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if let Ok(revert) = Revert::abi_decode(data, true) {
+        return Some(revert.reason);
+    }
+    if let Ok(panic) = Panic::abi_decode(data, true) {
+        return Some(panic.to_string());
+    }
+    let reason = match AssetPriceRegistryErrors::abi_decode(data, true).ok()? {
+        AssetPriceRegistryErrors::InvalidPriceFeed(_) => "Invalid price feed",
+        AssetPriceRegistryErrors::InvalidPriceFeedData(_) => "Invalid price feed data",
+        AssetPriceRegistryErrors::StalePriceFeedData(_) => "Stale price feed data",
+        AssetPriceRegistryErrors::AssetNotSupported(_) => "Asset not supported",
+    };
+    Some(reason.to_string())
+}
+
+/// Reason from an error message such as `execution reverted: Empty symbol`, for
+/// RPC providers that put the reason in the message instead of the data field.
+pub fn revert_reason_from_message(message: &str) -> Option<String> {
+    let (_, reason) = message.split_once("execution reverted: ")?;
+    let reason = reason.split(", data:").next()?.trim().trim_end_matches('"');
+    (!reason.is_empty()).then(|| reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    use crate::utils::helper::AssetPriceRegistry::AssetNotSupported;
+
+    #[test]
+    fn decodes_error_string() {
+        let data = Revert { reason: "Empty symbol".to_string() }.abi_encode();
+        assert_eq!(decode_revert_reason(&data).as_deref(), Some("Empty symbol"));
+    }
+
+    #[test]
+    fn decodes_panic_code() {
+        let data = Panic { code: U256::from(0x11) }.abi_encode();
+        let reason = decode_revert_reason(&data).unwrap();
+        assert!(reason.contains("overflow"), "{reason}");
+    }
+
+    #[test]
+    fn decodes_registry_custom_error() {
+        let data = AssetNotSupported {}.abi_encode();
+        assert_eq!(decode_revert_reason(&data).as_deref(), Some("Asset not supported"));
+    }
+
+    #[test]
+    fn rejects_unknown_selector() {
+        let mut data = vec![0xde, 0xad, 0xbe, 0xef];
+        data.extend_from_slice(&[0u8; 32]);
+        assert_eq!(decode_revert_reason(&data), None);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let data = Revert { reason: "Empty symbol".to_string() }.abi_encode();
+        assert_eq!(decode_revert_reason(&data[..data.len() - 1]), None);
+        assert_eq!(decode_revert_reason(&data[..2]), None);
+        assert_eq!(decode_revert_reason(&[]), None);
+    }
+
+    #[test]
+    fn reason_from_message() {
+        assert_eq!(
+            revert_reason_from_message("execution reverted: Empty symbol").as_deref(),
+            Some("Empty symbol")
+        );
+        assert_eq!(
+            revert_reason_from_message("execution reverted: Empty symbol, data: \"0x08c3\"")
+                .as_deref(),
+            Some("Empty symbol")
+        );
+        assert_eq!(revert_reason_from_message("execution reverted: "), None);
+        assert_eq!(revert_reason_from_message("execution reverted"), None);
+        assert_eq!(revert_reason_from_message("nonce too low"), None);
+    }
+}
@@ -0,0 +1,228 @@
+use alloy::primitives::Address;
+
+use crate::utils::config::Network;
+use crate::utils::error::BackendError;
+use crate::utils::price_feed::{latest_round, previous_round};
+use crate::utils::price_history::{window_points, HISTORY_RETENTION_SECONDS};
+
+pub const DEFAULT_MIN_SAMPLES: u32 = 2;
+pub const MAX_TWAP_WINDOW_SECONDS: u64 = HISTORY_RETENTION_SECONDS;
+// Rounds read by one live average, each one is an HTTPS outcall
+pub const MAX_TWAP_ROUNDS: usize = 100;
+
+/// A price that held from `timestamp` until the next sample
+#[derive(Clone, Copy, Debug)]
+pub struct PriceSample {
+    pub timestamp: u64,
+    pub price: i128,
+}
+
+/// Time weighted average over `[start, end]`. The window is covered from the first
+/// sample on, `coverage_gap_seconds` is the part before it that no sample explains.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeWeightedAverage {
+    pub price: i128,
+    pub samples: u32,
+    pub covered_seconds: u64,
+    pub coverage_gap_seconds: u64,
+}
+
+pub fn validate_window(window_seconds: u64) -> Result<(), BackendError> {
+    if window_seconds == 0 || window_seconds > MAX_TWAP_WINDOW_SECONDS {
+        return Err(BackendError::InvalidInput(format!(
+            "Window must be between 1 and {} seconds",
+            MAX_TWAP_WINDOW_SECONDS
+        )));
+    }
+    Ok(())
+}
+
+/// Samples from the recorded price history. Fails on points whose decimals differ,
+/// which happens when the asset was registered again with another feed.
+pub fn history_samples(
+    network: &Network,
+    owner_address: Address,
+    asset_address: Address,
+    start: u64,
+    end: u64,
+) -> Result<(Vec<PriceSample>, u8), BackendError> {
+    let points = window_points(network, owner_address, asset_address, start, end);
+    let decimals = points.last().map(|point| point.decimals).unwrap_or_default();
+
+    let mut samples = Vec::with_capacity(points.len());
+    for point in points {
+        if point.decimals != decimals {
+            return Err(BackendError::InvalidInput(format!(
+                "Price history of {:?} mixes {} and {} decimals",
+                asset_address, point.decimals, decimals
+            )));
+        }
+        let price = point
+            .raw_price
+            .parse()
+            .map_err(|_| BackendError::Overflow("price value out of range for i128".to_string()))?;
+        samples.push(PriceSample {
+            timestamp: point.timestamp,
+            price,
+        });
+    }
+    Ok((samples, decimals))
+}
+
+/// Samples from the feed's rounds, walking back from the latest one until the round
+/// current at `start` or `MAX_TWAP_ROUNDS`. Rounds without a valid answer are skipped.
+pub async fn round_samples(
+    network: &Network,
+    feed: Address,
+    start: u64,
+) -> Result<Vec<PriceSample>, BackendError> {
+    let mut round = latest_round(network, feed).await?;
+    let mut samples = Vec::new();
+    for _ in 0..MAX_TWAP_ROUNDS {
+        if round.answer > 0 && round.answered_in_round >= round.round_id {
+            samples.push(PriceSample {
+                timestamp: round.updated_at,
+                price: round.answer,
+            });
+        }
+        if round.updated_at <= start {
+            break;
+        }
+        match previous_round(network, feed, round.round_id).await? {
+            Some(previous) => round = previous,
+            None => break,
+        }
+    }
+    samples.reverse();
+    Ok(samples)
+}
+
+/// Weight each sample by how long it held within `[start, end]`. `samples` are oldest
+/// first; one before `start` counts from `start`. Fails with fewer than `min_samples`.
+pub fn time_weighted_average(
+    samples: &[PriceSample],
+    start: u64,
+    end: u64,
+    min_samples: u32,
+) -> Result<TimeWeightedAverage, BackendError> {
+    // Only the latest sample at or before `start` matters
+    let first = samples
+        .iter()
+        .rposition(|sample| sample.timestamp <= start)
+        .unwrap_or(0);
+    let samples: Vec<PriceSample> = samples[first..]
+        .iter()
+        .copied()
+        .filter(|sample| sample.timestamp <= end)
+        .collect();
+
+    if samples.is_empty() || (samples.len() as u32) < min_samples {
+        return Err(BackendError::AssetNotFound(format!(
+            "Found {} price samples in the window, {} required",
+            samples.len(),
+            min_samples.max(1)
+        )));
+    }
+
+    let overflow = || BackendError::Overflow("time weighted price sum out of range".to_string());
+    let mut weighted_sum: i128 = 0;
+    let mut covered_seconds: u64 = 0;
+    for (index, sample) in samples.iter().enumerate() {
+        let from = sample.timestamp.max(start);
+        let until = samples
+            .get(index + 1)
+            .map(|next| next.timestamp)
+            .unwrap_or(end);
+        let duration = until.saturating_sub(from);
+        weighted_sum = sample
+            .price
+            .checked_mul(duration as i128)
+            .and_then(|weighted| weighted_sum.checked_add(weighted))
+            .ok_or_else(overflow)?;
+        covered_seconds += duration;
+    }
+
+    // A single sample taken exactly at `end` holds for no time at all
+    let price = if covered_seconds == 0 {
+        samples[samples.len() - 1].price
+    } else {
+        weighted_sum / covered_seconds as i128
+    };
+    Ok(TimeWeightedAverage {
+        price,
+        samples: samples.len() as u32,
+        covered_seconds,
+        coverage_gap_seconds: (end - start).saturating_sub(covered_seconds),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(points: &[(u64, i128)]) -> Vec<PriceSample> {
+        points
+            .iter()
+            .map(|&(timestamp, price)| PriceSample { timestamp, price })
+            .collect()
+    }
+
+    #[test]
+    fn weights_unevenly_spaced_samples_by_duration() {
+        let samples = samples(&[(100, 10), (130, 20), (190, 40)]);
+
+        let average = time_weighted_average(&samples, 100, 200, 2).unwrap();
+
+        // 10 for 30s, 20 for 60s, 40 for 10s
+        assert_eq!(average.price, 19);
+        assert_eq!(average.samples, 3);
+        assert_eq!(average.covered_seconds, 100);
+        assert_eq!(average.coverage_gap_seconds, 0);
+    }
+
+    #[test]
+    fn single_sample_covers_the_window_from_its_timestamp() {
+        let samples = samples(&[(150, 50)]);
+
+        let average = time_weighted_average(&samples, 100, 200, 1).unwrap();
+
+        assert_eq!(average.price, 50);
+        assert_eq!(average.samples, 1);
+        assert_eq!(average.covered_seconds, 50);
+        assert_eq!(average.coverage_gap_seconds, 50);
+    }
+
+    #[test]
+    fn single_sample_below_min_samples_fails() {
+        let samples = samples(&[(150, 50)]);
+
+        let result = time_weighted_average(&samples, 100, 200, 2);
+
+        assert!(matches!(result, Err(BackendError::AssetNotFound(_))));
+    }
+
+    #[test]
+    fn single_sample_at_window_end_is_returned_as_is() {
+        let samples = samples(&[(200, 70)]);
+
+        let average = time_weighted_average(&samples, 100, 200, 1).unwrap();
+
+        assert_eq!(average.price, 70);
+        assert_eq!(average.covered_seconds, 0);
+        assert_eq!(average.coverage_gap_seconds, 100);
+    }
+
+    #[test]
+    fn clips_samples_to_the_window() {
+        // 10 is superseded before the window opens, 90 arrives after it closes
+        let samples = samples(&[(50, 10), (80, 30), (120, 60), (250, 90)]);
+
+        let average = time_weighted_average(&samples, 100, 200, 2).unwrap();
+
+        // 30 from 100 to 120, 60 from 120 to 200
+        assert_eq!(average.price, 54);
+        assert_eq!(average.samples, 2);
+        assert_eq!(average.covered_seconds, 100);
+        assert_eq!(average.coverage_gap_seconds, 0);
+    }
+}
@@ -0,0 +1,605 @@
+use alloy::{
+    network::{Ethereum, EthereumWallet, TransactionBuilder},
+    primitives::{Address, TxHash, U256},
+    providers::{Provider, ProviderBuilder},
+    signers::{icp::IcpSigner, Signer},
+    sol_types::SolCall,
+    transports::{icp::IcpConfig, RpcError, TransportError},
+};
+use candid::{CandidType, Deserialize, Principal};
+use std::str::FromStr;
+
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::error::BackendError;
+use crate::utils::fee_oracle::{estimate_fees, FeeEstimate, FeeOptions, FeeSpeed};
+use crate::utils::helper::{create_derivation_path, get_ecdsa_key_name};
+use crate::utils::nonce_manager::{
+    release_nonce, release_nonces, reserve_nonce, reserve_nonces, resync_reserved_nonces,
+};
+use crate::utils::price_cache::now_seconds;
+use crate::utils::tx_tracker::{track_transaction, TrackedTransaction};
+
+pub type TransactionRequest = <Ethereum as alloy::network::Network>::TransactionRequest;
+
+// Headroom on top of `eth_estimateGas`, in percent
+const GAS_LIMIT_BUFFER_PERCENT: u128 = 20;
+// Nodes reject replacements that raise fees by less than this, in percent
+const MIN_REPLACEMENT_BUMP_PERCENT: u128 = 10;
+pub const MIN_FEE_MULTIPLIER: f64 = 1.1;
+pub const MAX_FEE_MULTIPLIER: f64 = 10.0;
+const CANCEL_FEE_MULTIPLIER_PERMILLE: u128 = 1_250;
+const TRANSFER_GAS_LIMIT: u128 = 21_000;
+// Transactions one batch call can send
+pub const MAX_BATCH_SIZE: usize = 20;
+
+/// Record of a transaction handed to the network. Amounts are in wei, as decimal strings.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransactionSubmission {
+    pub transaction_hash: String,
+    pub network: String,
+    pub chain_id: u64,
+    pub from: String,
+    pub to: String,
+    pub nonce: u64,
+    pub gas_limit: String,
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+    /// Unix time in seconds
+    pub submitted_at: u64,
+}
+
+/// Threshold ECDSA signer derived for `principal`
+pub async fn create_signer(principal: &Principal) -> Result<IcpSigner, BackendError> {
+    let ecdsa_key_name = get_ecdsa_key_name();
+    let derivation_path = create_derivation_path(principal);
+    IcpSigner::new(derivation_path, &ecdsa_key_name, None)
+        .await
+        .map_err(|e| BackendError::SignerError(e.to_string()))
+}
+
+/// Sign `call` with the caller's derived key and send it to the registry on `network`.
+///
+/// Gas is estimated first, which also simulates the call, so a failing `require`
+/// comes back as `ContractRevert` before a nonce is used. The nonce is reserved
+/// right before sending. It is released again if the node rejects the transaction;
+/// after a failure that leaves open whether it was broadcast, it is re-read from the chain.
+///
+/// `method` and `arguments` describe the endpoint call and are kept with the
+/// tracked transaction. Fees come from the fee oracle, see `estimate_fees`.
+pub async fn submit_registry_call<C: SolCall>(
+    network: &Network,
+    method: &str,
+    arguments: String,
+    call: C,
+    fees: &FeeOptions,
+) -> Result<TransactionSubmission, BackendError> {
+    submit_call(network, network.registry_address, method, arguments, call, fees).await
+}
+
+/// Sign `call` with the caller's derived key and send it to `to` on `network`.
+pub async fn submit_call<C: SolCall>(
+    network: &Network,
+    to: Address,
+    method: &str,
+    arguments: String,
+    call: C,
+    fees: &FeeOptions,
+) -> Result<TransactionSubmission, BackendError> {
+    // Setup signer
+    let signer = create_signer(&ic_cdk::caller()).await?;
+    let from_address = signer.address();
+
+    let input = call.abi_encode();
+    let tx_request = TransactionRequest::default()
+        .with_from(from_address)
+        .with_to(to)
+        .with_input(input.clone())
+        .with_chain_id(network.chain_id);
+
+    // Estimate gas, reverts surface here with their decoded reason
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let estimated_gas = provider
+        .estimate_gas(&tx_request)
+        .await
+        .map_err(BackendError::from_call_error)?;
+    let gas_limit = estimated_gas * (100 + GAS_LIMIT_BUFFER_PERCENT) / 100;
+
+    // For EIP-1559 transactions, set max_fee_per_gas and max_priority_fee_per_gas
+    let fee_estimate = estimate_fees(network, fees).await?;
+    let max_fee_per_gas = fee_estimate.max_fee_per_gas;
+    let max_priority_fee_per_gas = fee_estimate.max_priority_fee_per_gas;
+
+    // Get nonce
+    let nonce = reserve_nonce(network, from_address).await?;
+
+    let tx_request = tx_request
+        .with_nonce(nonce)
+        .with_gas_limit(gas_limit)
+        .with_max_fee_per_gas(max_fee_per_gas)
+        .with_max_priority_fee_per_gas(max_priority_fee_per_gas);
+
+    // Send the transaction
+    let transaction_hash = match sign_and_send(network, signer, tx_request).await {
+        Ok(transaction_hash) => transaction_hash,
+        Err(SendError::Rejected(e)) => {
+            release_nonce(network, from_address, nonce);
+            return Err(e);
+        }
+        Err(SendError::Unknown(e)) => {
+            resync_after_failed_send(network, from_address, nonce + 1).await;
+            return Err(e);
+        }
+    };
+
+    let submission = new_submission(
+        network,
+        from_address,
+        to,
+        nonce,
+        transaction_hash,
+        gas_limit,
+        &fee_estimate,
+    );
+    track_transaction(&submission, method, arguments, input);
+    Ok(submission)
+}
+
+/// Sign several registry calls with the caller's derived key and send them with
+/// consecutive nonces. Items that failed to prepare are passed in as errors and
+/// keep their place in the returned list.
+///
+/// Every call is estimated before any nonce is reserved, so one that reverts gets
+/// its error and uses no nonce. Estimates run against the current chain state: a
+/// call that depends on an earlier call of the same batch fails its estimate.
+/// When sending fails, the calls after it are not sent, as their nonces would wait
+/// behind the gap, and the nonces from the failed one on are released.
+pub async fn submit_registry_batch<C: SolCall>(
+    network: &Network,
+    method: &str,
+    calls: Vec<Result<(String, C, FeeOptions), BackendError>>,
+) -> Result<Vec<Result<TransactionSubmission, BackendError>>, BackendError> {
+    // Setup signer
+    let signer = create_signer(&ic_cdk::caller()).await?;
+    let from_address = signer.address();
+    let to = network.registry_address;
+
+    // Estimate gas and fees of every call, reverts surface here with their decoded reason
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let mut fee_estimates: Vec<(FeeOptions, FeeEstimate)> = Vec::new();
+    let mut results = Vec::with_capacity(calls.len());
+    let mut ready = Vec::new();
+    for (index, prepared) in calls.into_iter().enumerate() {
+        let (arguments, call, fees) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                results.push(Err(e));
+                continue;
+            }
+        };
+        let input = call.abi_encode();
+        let tx_request = TransactionRequest::default()
+            .with_from(from_address)
+            .with_to(to)
+            .with_input(input.clone())
+            .with_chain_id(network.chain_id);
+        let estimated_gas = match provider.estimate_gas(&tx_request).await {
+            Ok(estimated_gas) => estimated_gas,
+            Err(e) => {
+                results.push(Err(BackendError::from_call_error(e)));
+                continue;
+            }
+        };
+        let fee_estimate = match fee_estimates.iter().find(|(options, _)| *options == fees) {
+            Some((_, fee_estimate)) => *fee_estimate,
+            None => {
+                let fee_estimate = estimate_fees(network, &fees).await?;
+                fee_estimates.push((fees, fee_estimate));
+                fee_estimate
+            }
+        };
+        let gas_limit = estimated_gas * (100 + GAS_LIMIT_BUFFER_PERCENT) / 100;
+
+        // Replaced once the call is sent
+        results.push(Err(BackendError::InvalidInput("Not sent".to_string())));
+        ready.push((index, arguments, input, tx_request, gas_limit, fee_estimate));
+    }
+    if ready.is_empty() {
+        return Ok(results);
+    }
+
+    // Get nonces, one reservation for the whole batch keeps them consecutive
+    let reserved = ready.len() as u64;
+    let first_nonce = reserve_nonces(network, from_address, reserved).await?;
+
+    let mut failed: Option<usize> = None;
+    for (offset, (index, arguments, input, tx_request, gas_limit, fee_estimate)) in
+        ready.into_iter().enumerate()
+    {
+        if let Some(failed) = failed {
+            results[index] = Err(BackendError::InvalidInput(format!(
+                "Not sent, item {} of the batch failed to send",
+                failed
+            )));
+            continue;
+        }
+        let nonce = first_nonce + offset as u64;
+        let tx_request = tx_request
+            .with_nonce(nonce)
+            .with_gas_limit(gas_limit)
+            .with_max_fee_per_gas(fee_estimate.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(fee_estimate.max_priority_fee_per_gas);
+
+        // Send the transaction
+        results[index] = match sign_and_send(network, signer.clone(), tx_request).await {
+            Ok(transaction_hash) => {
+                let submission = new_submission(
+                    network,
+                    from_address,
+                    to,
+                    nonce,
+                    transaction_hash,
+                    gas_limit,
+                    &fee_estimate,
+                );
+                track_transaction(&submission, method, arguments, input);
+                Ok(submission)
+            }
+            Err(SendError::Rejected(e)) => {
+                // Earlier items are out and hold their nonces, give back this one and the rest
+                release_nonces(network, from_address, nonce, first_nonce + reserved);
+                failed = Some(index);
+                Err(e)
+            }
+            Err(SendError::Unknown(e)) => {
+                resync_after_failed_send(network, from_address, first_nonce + reserved).await;
+                failed = Some(index);
+                Err(e)
+            }
+        };
+    }
+    Ok(results)
+}
+
+/// Network of a batch write. Every item has to target the same one.
+pub fn resolve_batch_network(networks: Vec<Option<String>>) -> Result<Network, BackendError> {
+    if networks.is_empty() || networks.len() > MAX_BATCH_SIZE {
+        return Err(BackendError::InvalidInput(format!(
+            "A batch must have between 1 and {} items",
+            MAX_BATCH_SIZE
+        )));
+    }
+    let mut resolved: Option<Network> = None;
+    for network in networks {
+        let network = resolve_network(network)?;
+        match &resolved {
+            Some(first) if first.name != network.name => {
+                return Err(BackendError::InvalidInput(format!(
+                    "All items of a batch must use the same network, got {} and {}",
+                    first.name, network.name
+                )));
+            }
+            Some(_) => {}
+            None => resolved = Some(network),
+        }
+    }
+    resolved.ok_or_else(|| BackendError::InvalidInput("Batch is empty".to_string()))
+}
+
+fn new_submission(
+    network: &Network,
+    from_address: Address,
+    to: Address,
+    nonce: u64,
+    transaction_hash: TxHash,
+    gas_limit: u128,
+    fee_estimate: &FeeEstimate,
+) -> TransactionSubmission {
+    TransactionSubmission {
+        transaction_hash: format!("{:?}", transaction_hash),
+        network: network.name.clone(),
+        chain_id: network.chain_id,
+        from: format!("{:?}", from_address),
+        to: format!("{:?}", to),
+        nonce,
+        gas_limit: gas_limit.to_string(),
+        max_fee_per_gas: fee_estimate.max_fee_per_gas.to_string(),
+        max_priority_fee_per_gas: fee_estimate.max_priority_fee_per_gas.to_string(),
+        submitted_at: now_seconds(),
+    }
+}
+
+/// Outcome of running a write as `eth_call` without broadcasting it. Amounts are in wei.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct SimulationResult {
+    pub network: String,
+    pub from: String,
+    pub to: String,
+    /// `false` when the call reverts, `revert_reason` then says why
+    pub success: bool,
+    pub revert_reason: Option<String>,
+    pub estimated_gas: Option<String>,
+    pub gas_limit: Option<String>,
+    pub max_fee_per_gas: Option<String>,
+    pub max_priority_fee_per_gas: Option<String>,
+    /// Estimated gas at the next base fee plus the priority fee
+    pub expected_fee: Option<String>,
+    /// Gas limit at the max fee, the most the transaction can cost
+    pub max_fee: Option<String>,
+}
+
+/// Run `call` from the caller's derived address as `eth_call`, then estimate gas and
+/// fees the way `submit_call` would. Nothing is signed or sent.
+pub async fn simulate_call<C: SolCall>(
+    network: &Network,
+    to: Address,
+    call: C,
+    fees: &FeeOptions,
+) -> Result<SimulationResult, BackendError> {
+    let signer = create_signer(&ic_cdk::caller()).await?;
+    let from_address = signer.address();
+
+    let tx_request = TransactionRequest::default()
+        .with_from(from_address)
+        .with_to(to)
+        .with_input(call.abi_encode())
+        .with_chain_id(network.chain_id);
+    let mut result = SimulationResult {
+        network: network.name.clone(),
+        from: format!("{:?}", from_address),
+        to: format!("{:?}", to),
+        success: false,
+        revert_reason: None,
+        estimated_gas: None,
+        gas_limit: None,
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        expected_fee: None,
+        max_fee: None,
+    };
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let estimated_gas = match provider.call(&tx_request).await {
+        Ok(_) => provider.estimate_gas(&tx_request).await,
+        Err(e) => Err(e),
+    };
+    let estimated_gas = match estimated_gas.map_err(BackendError::from_call_error) {
+        Ok(estimated_gas) => estimated_gas,
+        Err(BackendError::ContractRevert { reason }) => {
+            result.revert_reason = Some(reason);
+            return Ok(result);
+        }
+        Err(e) => return Err(e),
+    };
+    let gas_limit = estimated_gas * (100 + GAS_LIMIT_BUFFER_PERCENT) / 100;
+    let fee_estimate = estimate_fees(network, fees).await?;
+    let effective_gas_price = fee_estimate
+        .base_fee_per_gas
+        .saturating_add(fee_estimate.max_priority_fee_per_gas)
+        .min(fee_estimate.max_fee_per_gas);
+
+    Ok(SimulationResult {
+        success: true,
+        estimated_gas: Some(estimated_gas.to_string()),
+        gas_limit: Some(gas_limit.to_string()),
+        max_fee_per_gas: Some(fee_estimate.max_fee_per_gas.to_string()),
+        max_priority_fee_per_gas: Some(fee_estimate.max_priority_fee_per_gas.to_string()),
+        expected_fee: Some(estimated_gas.saturating_mul(effective_gas_price).to_string()),
+        max_fee: Some(gas_limit.saturating_mul(fee_estimate.max_fee_per_gas).to_string()),
+        ..result
+    })
+}
+
+/// How to replace a pending transaction
+#[derive(Clone, Copy, Debug)]
+pub enum Replacement {
+    /// Same call with fees multiplied by `fee_multiplier`
+    SpeedUp { fee_multiplier: f64 },
+    /// Zero-value transfer to self, which frees the nonce
+    Cancel,
+}
+
+/// Re-sign the nonce of a pending transaction of the caller with higher fees.
+///
+/// Nodes only accept a replacement that raises both fees by at least 10%, so the
+/// fees are bumped by at least that much. Whichever of the two transactions is
+/// mined, the tracker marks the other one as replaced.
+pub async fn replace_transaction(
+    original: &TrackedTransaction,
+    replacement: Replacement,
+    method: &str,
+    arguments: String,
+) -> Result<TransactionSubmission, BackendError> {
+    if original.caller != ic_cdk::caller() {
+        return Err(BackendError::Unauthorized(
+            "Only the caller who sent a transaction can replace it".to_string(),
+        ));
+    }
+    if !original.status.is_replaceable() {
+        return Err(BackendError::InvalidInput(format!(
+            "Transaction {} is already mined or replaced",
+            original.submission.transaction_hash
+        )));
+    }
+    let submission = &original.submission;
+    let network = resolve_network(Some(submission.network.clone()))?;
+
+    // Setup signer
+    let signer = create_signer(&ic_cdk::caller()).await?;
+    let from_address = signer.address();
+    if format!("{:?}", from_address) != submission.from {
+        return Err(BackendError::Unauthorized(
+            "Transaction was not sent from the caller's address".to_string(),
+        ));
+    }
+
+    let old_max_fee = parse_wei(&submission.max_fee_per_gas)?;
+    let old_priority_fee = parse_wei(&submission.max_priority_fee_per_gas)?;
+    let (to, input, gas_limit, multiplier_permille, floor) = match replacement {
+        Replacement::SpeedUp { fee_multiplier } => {
+            if !(MIN_FEE_MULTIPLIER..=MAX_FEE_MULTIPLIER).contains(&fee_multiplier) {
+                return Err(BackendError::InvalidInput(format!(
+                    "Fee multiplier must be between {} and {}",
+                    MIN_FEE_MULTIPLIER, MAX_FEE_MULTIPLIER
+                )));
+            }
+            let to = Address::from_str(&submission.to)
+                .map_err(|e| BackendError::InvalidAddress(e.to_string()))?;
+            (
+                to,
+                original.input.clone(),
+                parse_wei(&submission.gas_limit)?,
+                (fee_multiplier * 1000.0).round() as u128,
+                None,
+            )
+        }
+        Replacement::Cancel => {
+            // A cancellation should win the race, so pay at least the current fast fees
+            let fast = FeeOptions {
+                speed: Some(FeeSpeed::Fast),
+                max_fee_per_gas_cap: None,
+            };
+            (
+                from_address,
+                Vec::new(),
+                TRANSFER_GAS_LIMIT,
+                CANCEL_FEE_MULTIPLIER_PERMILLE,
+                Some(estimate_fees(&network, &fast).await?),
+            )
+        }
+    };
+
+    let mut max_priority_fee_per_gas = bump_fee(old_priority_fee, multiplier_permille);
+    let mut max_fee_per_gas = bump_fee(old_max_fee, multiplier_permille);
+    if let Some(floor) = floor {
+        max_priority_fee_per_gas = max_priority_fee_per_gas.max(floor.max_priority_fee_per_gas);
+        max_fee_per_gas = max_fee_per_gas.max(floor.max_fee_per_gas);
+    }
+    let max_fee_per_gas = max_fee_per_gas.max(max_priority_fee_per_gas);
+
+    let tx_request = TransactionRequest::default()
+        .with_from(from_address)
+        .with_to(to)
+        .with_input(input.clone())
+        .with_value(U256::ZERO)
+        .with_chain_id(network.chain_id)
+        .with_nonce(submission.nonce)
+        .with_gas_limit(gas_limit)
+        .with_max_fee_per_gas(max_fee_per_gas)
+        .with_max_priority_fee_per_gas(max_priority_fee_per_gas);
+
+    let transaction_hash = sign_and_send(&network, signer, tx_request).await?;
+
+    let resubmission = TransactionSubmission {
+        transaction_hash: format!("{:?}", transaction_hash),
+        network: network.name.clone(),
+        chain_id: network.chain_id,
+        from: submission.from.clone(),
+        to: format!("{:?}", to),
+        nonce: submission.nonce,
+        gas_limit: gas_limit.to_string(),
+        max_fee_per_gas: max_fee_per_gas.to_string(),
+        max_priority_fee_per_gas: max_priority_fee_per_gas.to_string(),
+        submitted_at: now_seconds(),
+    };
+    track_transaction(&resubmission, method, arguments, input);
+    Ok(resubmission)
+}
+
+// Raise `fee` by `multiplier_permille` / 1000, and always by more than 10%
+fn bump_fee(fee: u128, multiplier_permille: u128) -> u128 {
+    let multiplied = fee.saturating_mul(multiplier_permille) / 1000;
+    let minimum = fee.saturating_mul(100 + MIN_REPLACEMENT_BUMP_PERCENT) / 100 + 1;
+    multiplied.max(minimum)
+}
+
+fn parse_wei(value: &str) -> Result<u128, BackendError> {
+    value
+        .parse::<u128>()
+        .map_err(|_| BackendError::Overflow(format!("{} is not an amount in wei", value)))
+}
+
+/// A failed `eth_sendRawTransaction`
+enum SendError {
+    /// The node answered with an error, the nonce is still free
+    Rejected(BackendError),
+    /// The outcall failed or the node already has a transaction with this nonce,
+    /// so the transaction may be in the mempool
+    Unknown(BackendError),
+}
+
+impl From<SendError> for BackendError {
+    fn from(error: SendError) -> Self {
+        match error {
+            SendError::Rejected(e) | SendError::Unknown(e) => e,
+        }
+    }
+}
+
+impl From<TransportError> for SendError {
+    fn from(error: TransportError) -> Self {
+        let rejected = match &error {
+            RpcError::ErrorResp(payload) => !holds_nonce(&payload.message),
+            // Failed while filling or signing, before anything was sent
+            RpcError::LocalUsageError(_) | RpcError::SerError(_) => true,
+            _ => false,
+        };
+        let error = BackendError::from_call_error(error);
+        if rejected {
+            Self::Rejected(error)
+        } else {
+            Self::Unknown(error)
+        }
+    }
+}
+
+/// Node errors meaning a transaction with this nonce is already pending or mined
+fn holds_nonce(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["already known", "known transaction", "nonce too low", "replacement transaction underpriced"]
+        .iter()
+        .any(|known| message.contains(known))
+}
+
+// Keeps the nonce reserved if the count cannot be read, `resync_nonce` closes a gap later
+async fn resync_after_failed_send(network: &Network, address: Address, reserved_until: u64) {
+    if let Err(e) = resync_reserved_nonces(network, address, reserved_until).await {
+        ic_cdk::println!("Nonce resync for {:?} failed: {}", address, e);
+    }
+}
+
+async fn sign_and_send(
+    network: &Network,
+    signer: IcpSigner,
+    tx_request: TransactionRequest,
+) -> Result<TxHash, SendError> {
+    // Setup provider
+    let wallet = EthereumWallet::from(signer);
+    let config = IcpConfig::new(network.rpc_service.clone());
+    let provider = ProviderBuilder::new()
+        .with_gas_estimation()
+        .wallet(wallet)
+        .on_icp(config);
+
+    let pending_tx_builder = provider.send_transaction(tx_request).await?;
+    Ok(*pending_tx_builder.tx_hash())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_nonce_matches_node_errors_for_taken_nonces() {
+        assert!(holds_nonce("already known"));
+        assert!(holds_nonce("Known transaction: 0xabc"));
+        assert!(holds_nonce("nonce too low: next nonce 8, tx nonce 7"));
+        assert!(holds_nonce("replacement transaction underpriced"));
+    }
+
+    #[test]
+    fn holds_nonce_ignores_other_rejections() {
+        assert!(!holds_nonce("insufficient funds for gas * price + value"));
+        assert!(!holds_nonce("max fee per gas less than block base fee"));
+        assert!(!holds_nonce("intrinsic gas too low"));
+    }
+}
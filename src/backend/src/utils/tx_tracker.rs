@@ -0,0 +1,422 @@
+use std::{borrow::Cow, cell::RefCell, collections::BTreeMap, str::FromStr, time::Duration};
+
+use alloy::{
+    network::{ReceiptResponse, TransactionBuilder},
+    primitives::{Address, TxHash},
+    providers::{Provider, ProviderBuilder},
+    transports::icp::IcpConfig,
+};
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_cdk_timers::TimerId;
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+
+use crate::memory::{get_memory, Memory, TRANSACTIONS_MEMORY_ID};
+use crate::utils::config::{resolve_network, Network};
+use crate::utils::error::BackendError;
+use crate::utils::guard::FlagGuard;
+use crate::utils::price_cache::now_seconds;
+use crate::utils::tx_submitter::{TransactionRequest, TransactionSubmission};
+
+pub const POLL_INTERVAL_SECONDS: u64 = 30;
+// Receipt lookups per timer tick, each one is an HTTPS outcall
+pub const MAX_RECEIPTS_PER_POLL: usize = 10;
+// Oldest settled transactions are dropped beyond this, and the oldest of any status
+// once none is settled
+pub const MAX_TRACKED_TRANSACTIONS: u64 = 1_000;
+// Transactions still pending after this long stop being polled
+pub const PENDING_TIMEOUT_SECONDS: u64 = 24 * 60 * 60;
+// Dropped transactions keep being polled this long, in case the node that reported
+// them missing was behind
+pub const DROPPED_GRACE_SECONDS: u64 = 10 * 60;
+
+const UNKNOWN_REVERT_REASON: &str = "Transaction reverted without a reason";
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not mined yet, or mined with fewer confirmations than the network requires
+    Pending,
+    Confirmed { block_number: u64, gas_used: String },
+    /// Mined with status 0. `reason` comes from replaying the call and is best effort.
+    Reverted {
+        block_number: u64,
+        gas_used: String,
+        reason: String,
+    },
+    /// Another transaction with the same nonce was mined instead
+    Replaced { transaction_hash: String },
+    /// The sender's nonce moved past this transaction without it being mined, so a
+    /// transaction this canister did not track took the nonce or the node dropped it.
+    /// `since` is when this was first seen; it is polled for `DROPPED_GRACE_SECONDS` more.
+    Dropped { since: u64 },
+    /// Still not mined `PENDING_TIMEOUT_SECONDS` after submission, no longer polled.
+    /// It can still be replaced with `speed_up_transaction` or `cancel_transaction`.
+    TimedOut,
+}
+
+impl TransactionStatus {
+    /// Whether another transaction with the same nonce can still take its place
+    pub fn is_replaceable(&self) -> bool {
+        matches!(self, Self::Pending | Self::Dropped { .. } | Self::TimedOut)
+    }
+
+    /// Whether the receipt poller still looks this transaction up
+    pub fn is_polled(&self, now: u64) -> bool {
+        match self {
+            Self::Pending => true,
+            Self::Dropped { since } => now.saturating_sub(*since) < DROPPED_GRACE_SECONDS,
+            _ => false,
+        }
+    }
+}
+
+/// A transaction sent by one of the write endpoints, and what became of it
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TrackedTransaction {
+    pub submission: TransactionSubmission,
+    pub caller: Principal,
+    pub method: String,
+    pub arguments: String,
+    pub input: Vec<u8>,
+    pub status: TransactionStatus,
+    /// Unix time in seconds of the last receipt lookup, 0 if never checked
+    pub last_checked: u64,
+}
+
+impl Storable for TrackedTransaction {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).expect("failed to encode tracked transaction"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).expect("failed to decode tracked transaction")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+thread_local! {
+    // Keyed by transaction hash
+    static TRANSACTIONS: RefCell<StableBTreeMap<String, TrackedTransaction, Memory>> =
+        RefCell::new(StableBTreeMap::init(get_memory(TRANSACTIONS_MEMORY_ID)));
+
+    // Timers do not survive upgrades, so these live on the heap
+    static POLL_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+    static POLL_IN_PROGRESS: RefCell<bool> = const { RefCell::new(false) };
+}
+
+/// Record a submitted transaction and make sure the receipt poller is running.
+pub fn track_transaction(
+    submission: &TransactionSubmission,
+    method: &str,
+    arguments: String,
+    input: Vec<u8>,
+) {
+    let transaction = TrackedTransaction {
+        submission: submission.clone(),
+        caller: ic_cdk::caller(),
+        method: method.to_string(),
+        arguments,
+        input,
+        status: TransactionStatus::Pending,
+        last_checked: 0,
+    };
+    TRANSACTIONS.with_borrow_mut(|transactions| {
+        if transactions.len() >= MAX_TRACKED_TRANSACTIONS {
+            evict_oldest(transactions);
+        }
+        transactions.insert(submission.transaction_hash.clone(), transaction);
+    });
+    start_receipt_polling();
+}
+
+/// Remove the oldest settled transaction, or the oldest one still pending when
+/// nothing has settled, so the map never grows past `MAX_TRACKED_TRANSACTIONS`.
+fn evict_oldest(transactions: &mut StableBTreeMap<String, TrackedTransaction, Memory>) {
+    let now = now_seconds();
+    let oldest_settled = transactions
+        .iter()
+        .filter(|(_, transaction)| !transaction.status.is_polled(now))
+        .min_by_key(|(_, transaction)| transaction.submission.submitted_at)
+        .map(|(hash, _)| hash);
+    let oldest = oldest_settled.or_else(|| {
+        transactions
+            .iter()
+            .min_by_key(|(_, transaction)| transaction.submission.submitted_at)
+            .map(|(hash, _)| hash)
+    });
+    if let Some(hash) = oldest {
+        transactions.remove(&hash);
+    }
+}
+
+pub fn get_tracked_transaction(transaction_hash: &str) -> Option<TrackedTransaction> {
+    let key = normalize_hash(transaction_hash)?;
+    TRANSACTIONS.with_borrow(|transactions| transactions.get(&key))
+}
+
+/// Hashes are stored as `{:?}` prints them, lowercase with a `0x` prefix
+fn normalize_hash(transaction_hash: &str) -> Option<String> {
+    TxHash::from_str(transaction_hash.trim())
+        .ok()
+        .map(|hash| format!("{:?}", hash))
+}
+
+fn has_polled() -> bool {
+    let now = now_seconds();
+    TRANSACTIONS.with_borrow(|transactions| {
+        transactions
+            .iter()
+            .any(|(_, transaction)| transaction.status.is_polled(now))
+    })
+}
+
+/// Start the receipt poller if transactions are pending and it is not running yet.
+/// Called after install, after every upgrade and whenever a transaction is tracked.
+pub fn start_receipt_polling() {
+    if POLL_TIMER.with_borrow(|timer| timer.is_some()) || !has_polled() {
+        return;
+    }
+    let timer_id = ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(POLL_INTERVAL_SECONDS),
+        || ic_cdk::spawn(poll_receipts()),
+    );
+    POLL_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+fn stop_receipt_polling() {
+    if let Some(timer_id) = POLL_TIMER.with_borrow_mut(|timer| timer.take()) {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+}
+
+/// Look up receipts of the pending and recently dropped transactions checked least
+/// recently, and stop the poller once none is left.
+pub async fn poll_receipts() {
+    let Some(_polling) = FlagGuard::acquire(&POLL_IN_PROGRESS) else {
+        return;
+    };
+
+    let now = now_seconds();
+    expire_pending(now);
+
+    let mut pending: Vec<TrackedTransaction> = TRANSACTIONS.with_borrow(|transactions| {
+        transactions
+            .iter()
+            .map(|(_, transaction)| transaction)
+            .filter(|transaction| transaction.status.is_polled(now))
+            .collect()
+    });
+    pending.sort_by_key(|transaction| transaction.last_checked);
+    pending.truncate(MAX_RECEIPTS_PER_POLL);
+
+    // Latest block per network, fetched once per tick
+    let mut heads: BTreeMap<String, Option<(Network, u64)>> = BTreeMap::new();
+    for transaction in pending {
+        let network_name = transaction.submission.network.clone();
+        if !heads.contains_key(&network_name) {
+            let head = fetch_head(&network_name).await;
+            if let Err(e) = &head {
+                ic_cdk::println!("Skipping receipts on {}: {}", network_name, e);
+            }
+            heads.insert(network_name.clone(), head.ok());
+        }
+        let Some(Some((network, head))) = heads.get(&network_name) else {
+            continue;
+        };
+
+        match check_transaction(network, *head, &transaction).await {
+            Ok(status) => store_status(&transaction.submission.transaction_hash, status),
+            Err(e) => {
+                ic_cdk::println!(
+                    "Receipt lookup failed for {}: {}",
+                    transaction.submission.transaction_hash,
+                    e
+                );
+                store_status(&transaction.submission.transaction_hash, transaction.status);
+            }
+        }
+    }
+
+    if !has_polled() {
+        stop_receipt_polling();
+    }
+}
+
+/// Mark transactions pending for longer than `PENDING_TIMEOUT_SECONDS` as timed out,
+/// including those on networks that were removed since.
+fn expire_pending(now: u64) {
+    let expired: Vec<String> = TRANSACTIONS.with_borrow(|transactions| {
+        transactions
+            .iter()
+            .filter(|(_, transaction)| {
+                transaction.status == TransactionStatus::Pending
+                    && now.saturating_sub(transaction.submission.submitted_at)
+                        > PENDING_TIMEOUT_SECONDS
+            })
+            .map(|(hash, _)| hash)
+            .collect()
+    });
+    for hash in expired {
+        store_status(&hash, TransactionStatus::TimedOut);
+    }
+}
+
+async fn fetch_head(network_name: &str) -> Result<(Network, u64), BackendError> {
+    let network = resolve_network(Some(network_name.to_string()))?;
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    Ok((network, head))
+}
+
+async fn check_transaction(
+    network: &Network,
+    head: u64,
+    transaction: &TrackedTransaction,
+) -> Result<TransactionStatus, BackendError> {
+    let submission = &transaction.submission;
+    let hash = TxHash::from_str(&submission.transaction_hash)
+        .map_err(|e| BackendError::InvalidInput(e.to_string()))?;
+
+    let from = Address::from_str(&submission.from)
+        .map_err(|e| BackendError::InvalidAddress(e.to_string()))?;
+
+    let provider = ProviderBuilder::new().on_icp(IcpConfig::new(network.rpc_service.clone()));
+    // Read before the receipt: a transaction mined in between then still has one
+    let mined_count = provider
+        .get_transaction_count(from)
+        .latest()
+        .await
+        .map_err(BackendError::from_call_error)?;
+    if mined_count <= submission.nonce {
+        return Ok(TransactionStatus::Pending);
+    }
+
+    let Some(receipt) = provider
+        .get_transaction_receipt(hash)
+        .await
+        .map_err(BackendError::from_call_error)?
+    else {
+        return Ok(TransactionStatus::Dropped {
+            since: now_seconds(),
+        });
+    };
+    let Some(block_number) = receipt.block_number() else {
+        return Ok(TransactionStatus::Pending);
+    };
+    if head.saturating_sub(block_number) + 1 < network.confirmations {
+        return Ok(TransactionStatus::Pending);
+    }
+
+    let gas_used = receipt.gas_used.to_string();
+    if receipt.status() {
+        return Ok(TransactionStatus::Confirmed {
+            block_number,
+            gas_used,
+        });
+    }
+
+    // Receipts carry no revert data, so replay the call to recover the reason
+    let to = Address::from_str(&submission.to)
+        .map_err(|e| BackendError::InvalidAddress(e.to_string()))?;
+    let replay = TransactionRequest::default()
+        .with_from(from)
+        .with_to(to)
+        .with_input(transaction.input.clone());
+    let reason = match provider.call(&replay).await.map_err(BackendError::from_call_error) {
+        Err(BackendError::ContractRevert { reason }) => reason,
+        _ => UNKNOWN_REVERT_REASON.to_string(),
+    };
+    Ok(TransactionStatus::Reverted {
+        block_number,
+        gas_used,
+        reason,
+    })
+}
+
+/// Update a pending or dropped transaction. Once it is mined, other transactions with
+/// the same sender and nonce can never be, so they are marked as replaced.
+fn store_status(transaction_hash: &str, status: TransactionStatus) {
+    TRANSACTIONS.with_borrow_mut(|transactions| {
+        let Some(transaction) = transactions.get(&transaction_hash.to_string()) else {
+            return;
+        };
+        // Replaced or timed out while its receipt was being fetched
+        if !matches!(
+            transaction.status,
+            TransactionStatus::Pending | TransactionStatus::Dropped { .. }
+        ) {
+            return;
+        }
+        // Keep when it was first seen dropped, so the grace period does not restart
+        let status = match (&transaction.status, status) {
+            (TransactionStatus::Dropped { since }, TransactionStatus::Dropped { .. }) => {
+                TransactionStatus::Dropped { since: *since }
+            }
+            (_, status) => status,
+        };
+
+        if matches!(
+            status,
+            TransactionStatus::Confirmed { .. } | TransactionStatus::Reverted { .. }
+        ) {
+            let replaced: Vec<(String, TrackedTransaction)> = transactions
+                .iter()
+                .filter(|(hash, other)| {
+                    hash != transaction_hash
+                        && other.status.is_replaceable()
+                        && other.submission.network == transaction.submission.network
+                        && other.submission.from == transaction.submission.from
+                        && other.submission.nonce == transaction.submission.nonce
+                })
+                .collect();
+            for (hash, other) in replaced {
+                transactions.insert(
+                    hash,
+                    TrackedTransaction {
+                        status: TransactionStatus::Replaced {
+                            transaction_hash: transaction_hash.to_string(),
+                        },
+                        ..other
+                    },
+                );
+            }
+        }
+
+        transactions.insert(
+            transaction_hash.to_string(),
+            TrackedTransaction {
+                status,
+                last_checked: now_seconds(),
+                ..transaction
+            },
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_is_polled_during_grace_period() {
+        let dropped = TransactionStatus::Dropped { since: 1_000 };
+        assert!(dropped.is_polled(1_000));
+        assert!(dropped.is_polled(1_000 + DROPPED_GRACE_SECONDS - 1));
+        assert!(!dropped.is_polled(1_000 + DROPPED_GRACE_SECONDS));
+        assert!(dropped.is_replaceable());
+    }
+
+    #[test]
+    fn only_pending_and_dropped_are_polled() {
+        assert!(TransactionStatus::Pending.is_polled(u64::MAX));
+        assert!(!TransactionStatus::TimedOut.is_polled(0));
+        assert!(!TransactionStatus::Confirmed {
+            block_number: 1,
+            gas_used: "21000".to_string()
+        }
+        .is_polled(0));
+    }
+}
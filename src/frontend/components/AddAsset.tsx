@@ -7,7 +7,7 @@ const AddAsset = () => {
     const { addAsset, isFetchingAddress, ethAddress } = useAddAssetService();
     const [isSubmitting, setIsSubmitting] = useState(false);
     const [error, setError] = useState<{ message: string, details?: string } | null>(null);
-    const [success, setSuccess] = useState<{ message: string, txHash?: string } | null>(null);
+    const [success, setSuccess] = useState<{ message: string, txHash?: string, warnings: string[] } | null>(null);
 
     // Form state
     const [formData, setFormData] = useState({
@@ -36,16 +36,16 @@ const AddAsset = () => {
             const result = await addAsset(
                 formData.asset_address,
                 BigInt(formData.stale_price_threshold),
-                Number(formData.token_decimals),
+                formData.token_decimals === "" ? undefined : Number(formData.token_decimals),
                 formData.symbol,
                 formData.price_feed
             );
-            const txHashMatch = result.match(/Transaction hash: 0x([a-fA-F0-9]{64})/);
-            const txHash = txHashMatch ? `0x${txHashMatch[1]}` : null;
+            const txHash = result.submission.transaction_hash;
 
             setSuccess({
                 message: "Asset successfully added to the Asset Price Registry contract",
-                txHash: txHash || undefined
+                txHash: txHash || undefined,
+                warnings: result.feed_warnings
             });
 
             setFormData({
@@ -150,6 +150,13 @@ const AddAsset = () => {
                                             </p>
                                         </div>
                                     )}
+                                    {success.warnings.length > 0 && (
+                                        <ul className="mt-2 text-sm text-muted-foreground list-disc list-inside">
+                                            {success.warnings.map((warning, i) => (
+                                                <li key={i}>Price feed warning: {warning}</li>
+                                            ))}
+                                        </ul>
+                                    )}
                                     <p className="text-sm text-muted-foreground mt-2">
                                         The asset is now available in your Asset Price Registry contract.
                                     </p>
@@ -224,7 +231,7 @@ const AddAsset = () => {
                                     min="0"
                                     value={formData.token_decimals}
                                     onChange={handleChange}
-                                    required
+                                    placeholder="Read from the token"
                                     disabled={isSubmitting}
                                     className="w-full px-4 py-3 bg-muted border border-border rounded-lg text-foreground focus:outline-none focus:ring-2 focus:ring-primary focus:ring-opacity-50 transition-all disabled:opacity-50"
                                 />
@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useAllAssetWithPrice } from "@/services/get_all_assets_with_prices";
 import { Skeleton } from "./ui/skeleton";
 import { ScanSearch } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type AssetWithPrice = {
   address: string;
@@ -39,7 +40,7 @@ const AllAssetWithPrice = () => {
       if ('Ok' in result) {
         setAssets(result.Ok);
       } else if ('Err' in result) {
-        setError(result.Err);
+        setError(formatBackendError(result.Err));
       }
     } catch (e) {
       setError("Failed to fetch assets with prices");
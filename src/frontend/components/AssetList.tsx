@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useAssetService } from "@/services/get_all_asset";
 import { Skeleton } from "./ui/skeleton";
 import { ScanSearch } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type AssetInfo = {
   address: string;
@@ -30,7 +31,7 @@ const AssetList = () => {
       if ('Ok' in result) {
         setAssets(result.Ok);
       } else if ('Err' in result) {
-        setError(result.Err);
+        setError(formatBackendError(result.Err));
       }
     } catch (e) {
       setError("Failed to fetch assets");
@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useConversionService } from "@/services/convert_tokens";
 import { Skeleton } from "./ui/skeleton";
 import { ArrowRightLeft, Wallet } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type RawConversionData = {
     price: string;
@@ -73,7 +74,7 @@ const TokenConverter = () => {
             }
 
             if ('Err' in result) {
-                throw new Error(result.Err);
+                throw new Error(formatBackendError(result.Err));
             }
 
             const conversionResults = result.Ok.map(res => ({
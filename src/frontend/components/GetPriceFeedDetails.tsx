@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useGetPriceFeedDetails } from "@/services/get_price_feed_details";
 import { Skeleton } from "./ui/skeleton";
 import { BarChart2 } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type PriceFeedDetails = {
   updated_at: bigint;
@@ -34,7 +35,7 @@ const GetPriceFeedDetails = () => {
       if ('Ok' in result) {
         setDetails(result.Ok);
       } else if ('Err' in result) {
-        setError(result.Err);
+        setError(formatBackendError(result.Err));
       }
     } catch (e) {
       setError("Failed to fetch price feed details");
@@ -18,8 +18,7 @@ const RemoveAsset = () => {
 
         try {
             const result = await removeAsset(assetAddress);
-            const txHashMatch = result.match(/Transaction hash: 0x([a-fA-F0-9]{64})/);
-            const txHash = txHashMatch ? `0x${txHashMatch[1]}` : null;
+            const txHash = result.transaction_hash;
 
             setSuccess({
                 message: "Asset successfully removed from the Asse tPrice Registry contract",
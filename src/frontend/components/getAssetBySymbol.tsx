@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useGetAssetBySymbol } from "@/services/get_asset_by_symbol";
 import { Skeleton } from "./ui/skeleton";
 import { Search } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type AssetInfoSymbol = {
   asset_address: string;
@@ -34,7 +35,7 @@ const GetAssetBySymbol = () => {
       if ('Ok' in result) {
         setAsset(result.Ok);
       } else if ('Err' in result) {
-        setError(result.Err);
+        setError(formatBackendError(result.Err));
       }
     } catch (e) {
       setError("Failed to fetch asset");
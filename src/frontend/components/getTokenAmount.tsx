@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useGetTokenAmount } from "@/services/get_token_amount";
 import { Skeleton } from "./ui/skeleton";
 import { Calculator } from "lucide-react";
+import { formatBackendError } from "@/lib/backend-error";
 
 type TokenAmountResult = {
   raw_amount: string;
@@ -40,7 +41,7 @@ const GetTokenAmount = () => {
       if ('Ok' in response) {
         setResult(response.Ok);
       } else if ('Err' in response) {
-        setError(response.Err);
+        setError(formatBackendError(response.Err));
       }
     } catch (e) {
       setError("Failed to calculate token amount");
@@ -2,6 +2,7 @@ import { useActor } from "@/actor";
 import { useQuery } from "@tanstack/react-query";
 import useHandleAgentError from "./useHandleAgentError";
 import { useInternetIdentity } from "ic-use-internet-identity";
+import { formatBackendError } from "@/lib/backend-error";
 
 export default function useEthAddress() {
   const { actor: backend } = useActor();
@@ -34,7 +35,7 @@ export default function useEthAddress() {
         }
 
         if ('Err' in result) {
-          throw new Error(result.Err);
+          throw new Error(formatBackendError(result.Err));
         }
 
         const address = result.Ok;
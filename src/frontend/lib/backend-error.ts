@@ -0,0 +1,21 @@
+import type { BackendError } from "../../backend/declarations/backend.did";
+
+// Mirrors the Display impl of BackendError in the canister
+export function formatBackendError(error: BackendError): string {
+  if ('InvalidAddress' in error) return `Invalid address: ${error.InvalidAddress}`;
+  if ('ChecksumMismatch' in error) {
+    const { provided, expected } = error.ChecksumMismatch;
+    return `Invalid checksum for ${provided}. Use: ${expected}`;
+  }
+  if ('InvalidInput' in error) return `Invalid input: ${error.InvalidInput}`;
+  if ('Unauthorized' in error) return `Unauthorized: ${error.Unauthorized}`;
+  if ('SignerError' in error) return `Failed to create signer: ${error.SignerError}`;
+  if ('RpcError' in error) return `RPC error: ${error.RpcError}`;
+  if ('ContractRevert' in error) return `Contract reverted: ${error.ContractRevert.reason}`;
+  if ('StalePrice' in error) return `Price for ${error.StalePrice.asset} is invalid or stale`;
+  if ('AssetNotFound' in error) return `Not found: ${error.AssetNotFound}`;
+  if ('Overflow' in error) return `Overflow: ${error.Overflow}`;
+  if ('InsufficientCycles' in error) return `Insufficient cycles: ${error.InsufficientCycles}`;
+  const { agreeing, providers, required } = error.InconsistentResults;
+  return `Providers disagree: ${agreeing} of ${providers} returned the same result, ${required} required`;
+}
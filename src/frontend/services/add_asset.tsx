@@ -2,6 +2,8 @@ import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
 import useEthAddress from "@/hooks/useEthAddress";
 import { useQueryClient } from "@tanstack/react-query";
+import type { AddAssetArgs } from "../../backend/declarations/backend.did";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useAddAssetService = () => {
   const { actor: backend } = useActor();
@@ -12,7 +14,7 @@ export const useAddAssetService = () => {
   const addAsset = async (
     asset_address: string,
     stale_price_threshold: bigint,
-    token_decimals: number,
+    token_decimals: number | undefined,
     symbol: string,
     price_feed: string
   ) => {
@@ -29,12 +31,16 @@ export const useAddAssetService = () => {
     }
 
     try {
-      const args = {
+      const args: AddAssetArgs = {
         asset_address,
         stale_price_threshold,
-        token_decimals,
-        symbol,
-        price_feed
+        token_decimals: token_decimals === undefined ? [] : [token_decimals],
+        symbol: symbol ? [symbol] : [],
+        price_feed,
+        detect_metadata: [token_decimals === undefined],
+        override_feed_validation: [],
+        fee: [],
+        network: []
       };
       
       const result = await backend.add_asset(args);
@@ -44,7 +50,7 @@ export const useAddAssetService = () => {
         });
         return result.Ok;
       } else if ('Err' in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       
       throw new Error('Unexpected response from backend');
@@ -1,6 +1,7 @@
 import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
 import { useAssetService } from "./get_all_asset";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useConversionService = () => {
   const { actor: backend } = useActor();
@@ -16,7 +17,7 @@ export const useConversionService = () => {
       const assetsResult = await getAllAssets(address);
       
       if ('Err' in assetsResult) {
-        throw new Error(assetsResult.Err);
+        throw new Error(formatBackendError(assetsResult.Err));
       }
       
       const assets = assetsResult.Ok;
@@ -26,7 +27,7 @@ export const useConversionService = () => {
 
       const tokenAmounts = new Array(assets.length).fill(tokenAmount);
       
-      const result = await backend.convert_tokens_to_usd(address, tokenAmounts);
+      const result = await backend.convert_tokens_to_usd_live(address, tokenAmounts, []);
       return result;
     } catch (e) {
       handleAgentError(e);
@@ -45,7 +46,7 @@ export const useConversionService = () => {
       
       // Unwrap the Result type
       if ('Err' in assetsResult) {
-        throw new Error(assetsResult.Err);
+        throw new Error(formatBackendError(assetsResult.Err));
       }
       
       const assets = assetsResult.Ok;
@@ -56,7 +57,7 @@ export const useConversionService = () => {
       // Create array with same USD amount for all assets
       const usdAmounts = new Array(assets.length).fill(usdAmount);
       
-      const result = await backend.convert_usd_to_tokens(address, usdAmounts);
+      const result = await backend.convert_usd_to_tokens_live(address, usdAmounts, []);
       return result;
     } catch (e) {
       handleAgentError(e);
@@ -12,7 +12,11 @@ export const useAssetService = () => {
     }
 
     try {
-      const result = await backend.get_all_assets(address);
+      // The query only answers for owners already in the price cache
+      const result = await backend.get_all_assets(address, []);
+      if ('Err' in result && 'AssetNotFound' in result.Err) {
+        return await backend.get_all_assets_live(address, []);
+      }
       return result;
     } catch (e) {
       handleAgentError(e);
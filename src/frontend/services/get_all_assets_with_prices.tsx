@@ -11,7 +11,11 @@ export const useAllAssetWithPrice = () => {
     }
 
     try {
-      const result = await backend.get_all_assets_with_prices(address);
+      // The query only answers for owners already in the price cache
+      const result = await backend.get_all_assets_with_prices(address, []);
+      if ('Err' in result && 'AssetNotFound' in result.Err) {
+        return await backend.get_all_assets_with_prices_live(address, []);
+      }
       return result;
     } catch (e) {
       handleAgentError(e);
@@ -12,7 +12,7 @@ export const useGetAssetBySymbol = () => {
     }
 
     try {
-      const result = await backend.get_asset_by_symbol(address, symbol);
+      const result = await backend.get_asset_by_symbol_live(address, symbol, []);
       return result;
     } catch (e) {
       handleAgentError(e);
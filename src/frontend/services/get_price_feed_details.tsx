@@ -11,7 +11,7 @@ export const useGetPriceFeedDetails = () => {
     }
 
     try {
-      const result = await backend.get_price_feed_details(assetAddress, priceFeed);
+      const result = await backend.get_price_feed_details_live(assetAddress, priceFeed, []);
       return result;
     } catch (e) {
       handleAgentError(e);
@@ -16,11 +16,12 @@ export const useGetTokenAmount = () => {
     }
 
     try {
-      const result = await backend.get_token_amount(
+      const result = await backend.get_token_amount_live(
         owneraddress,
         assetAddress,
         amount,
-        decimals
+        decimals,
+        []
       );
       return result;
     } catch (e) {
@@ -1,5 +1,6 @@
 import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useGetTokenPrice = () => {
 
@@ -12,9 +13,9 @@ export const useGetTokenPrice = () => {
     }
 
     try {
-      const result = await backend.get_token_price(ownerAddress, assetAddress);
+      const result = await backend.get_token_price(ownerAddress, assetAddress, []);
       if ("Err" in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       return result.Ok;
     } catch (e) {
@@ -1,5 +1,6 @@
 import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useGetTokenPriceBySymbol = () => {
   const { actor: backend } = useActor();
@@ -11,9 +12,9 @@ export const useGetTokenPriceBySymbol = () => {
     }
 
     try {
-      const result = await backend.get_token_price_by_symbol(ownerAddress, symbol);
+      const result = await backend.get_token_price_by_symbol(ownerAddress, symbol, []);
       if ("Err" in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       return result.Ok;
     } catch (e) {
@@ -1,5 +1,6 @@
 import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useGetUsdValue = () => {
   const { actor: backend } = useActor();
@@ -16,15 +17,16 @@ export const useGetUsdValue = () => {
     }
 
     try {
-      const result = await backend.get_usd_value(
+      const result = await backend.get_usd_value_live(
         ownerAddress,
         assetAddress,
         tokenAmount,
-        tokenDecimals
+        tokenDecimals,
+        []
       );
       
       if ("Err" in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       
       return result.Ok;
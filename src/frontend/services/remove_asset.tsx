@@ -3,6 +3,8 @@ import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
 import useEthAddress from "@/hooks/useEthAddress";
 import { useQueryClient } from "@tanstack/react-query";
+import type { RemoveAssetArgs } from "../../backend/declarations/backend.did";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useRemoveAssetService = () => {
   const { actor: backend } = useActor();
@@ -24,8 +26,10 @@ export const useRemoveAssetService = () => {
     }
 
     try {
-      const args = {
-        asset_address
+      const args: RemoveAssetArgs = {
+        asset_address,
+        fee: [],
+        network: []
       };
       
       const result = await backend.remove_asset(args);
@@ -35,7 +39,7 @@ export const useRemoveAssetService = () => {
         });
         return result.Ok;
       } else if ('Err' in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       
       throw new Error('Unexpected response from backend');
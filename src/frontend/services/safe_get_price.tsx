@@ -1,5 +1,6 @@
 import { useActor } from "@/actor";
 import useHandleAgentError from "@/hooks/useHandleAgentError";
+import { formatBackendError } from "@/lib/backend-error";
 
 export const useSafeGetPrice = () => {
   const { actor: backend } = useActor();
@@ -11,9 +12,9 @@ export const useSafeGetPrice = () => {
     }
 
     try {
-      const result = await backend.safe_get_price(ownerAddress, assetAddress);
+      const result = await backend.safe_get_price(ownerAddress, assetAddress, []);
       if ("Err" in result) {
-        throw new Error(result.Err);
+        throw new Error(formatBackendError(result.Err));
       }
       return result.Ok;
     } catch (e) {